@@ -44,13 +44,30 @@ impl Response {
             quote! { #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)] }
         };
 
+        // Responses without any fields are common for endpoints that only change state on the
+        // server, so they get a `Default` implementation and a constructor for free.
+        let is_empty = self.fields.is_empty();
+        let default_derive = is_empty.then(|| quote! { ::std::default::Default, });
+
         let response_ident = Ident::new("Response", self.response_kw.span());
+        let constructor = is_empty.then(|| {
+            quote! {
+                impl #response_ident {
+                    /// Creates an empty `Response`.
+                    pub fn new() -> Self {
+                        Self {}
+                    }
+                }
+            }
+        });
+
         let fields = &self.fields;
         quote! {
             #[doc = #docs]
             #[derive(
                 Clone,
                 Debug,
+                #default_derive
                 #ruma_api_macros::Response,
                 #ruma_serde::Outgoing,
                 #ruma_serde::_FakeDeriveSerde,
@@ -62,6 +79,8 @@ impl Response {
             pub struct #response_ident {
                 #fields
             }
+
+            #constructor
         }
     }
 }
@@ -1,7 +1,10 @@
+use std::collections::BTreeSet;
+
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
+use syn::{GenericArgument, PathArguments, Type};
 
-use crate::auth_scheme::AuthScheme;
+use crate::{auth_scheme::AuthScheme, util::collect_lifetime_idents};
 
 use super::{Request, RequestField, RequestFieldKind};
 
@@ -34,7 +37,7 @@ impl Request {
                 format_args.push(quote! {
                     #percent_encoding::utf8_percent_encode(
                         &::std::string::ToString::to_string(&self.#path_var),
-                        #percent_encoding::NON_ALPHANUMERIC,
+                        #ruma_api::exports::PATH_SEGMENT_ENCODE_SET,
                     )
                 });
                 format_string.replace_range(start_of_segment..end_of_segment, "{}");
@@ -44,7 +47,7 @@ impl Request {
                 format_args!(#format_string, #(#format_args),*)
             }
         } else {
-            quote! { metadata.path.to_owned() }
+            quote! { self::METADATA.path }
         };
 
         let request_query_string = if let Some(field) = self.query_map_field() {
@@ -184,7 +187,17 @@ impl Request {
             }
         });
 
+        let owned_impl = self.expand_owned_outgoing(ruma_api);
+
         quote! {
+            impl #impl_generics Request #ty_generics #where_clause {
+                /// Returns the path of this request, with the values of the path parameters
+                /// substituted and percent-encoded.
+                pub fn path(&self) -> ::std::string::String {
+                    ::std::format!("{}", #request_path_string)
+                }
+            }
+
             #[automatically_derived]
             #[cfg(feature = "client")]
             impl #impl_generics #ruma_api::OutgoingRequest for Request #ty_generics #where_clause {
@@ -198,14 +211,12 @@ impl Request {
                     base_url: &::std::primitive::str,
                     access_token: #ruma_api::SendAccessToken<'_>,
                 ) -> ::std::result::Result<#http::Request<T>, #ruma_api::error::IntoHttpError> {
-                    let metadata = self::METADATA;
-
                     let mut req_builder = #http::Request::builder()
                         .method(#http::Method::#method)
                         .uri(::std::format!(
                             "{}{}{}",
                             base_url.strip_suffix('/').unwrap_or(base_url),
-                            #request_path_string,
+                            self.path(),
                             #request_query_string,
                         ))
                         .header(
@@ -224,7 +235,77 @@ impl Request {
             }
 
             #non_auth_impl
+
+            #owned_impl
+        }
+    }
+
+    /// Produces an `OutgoingRequest` implementation for `IncomingRequest`, the owned variant of a
+    /// request with lifetimes, that borrows its fields to create a `Request` and converts that.
+    ///
+    /// This is only possible if every field with a lifetime is a reference, or an optional
+    /// reference, to a type without lifetimes. Otherwise, nothing is generated.
+    fn expand_owned_outgoing(&self, ruma_api: &TokenStream) -> Option<TokenStream> {
+        if !self.has_lifetimes() {
+            return None;
         }
+
+        let bytes = quote! { #ruma_api::exports::bytes };
+        let http = quote! { #ruma_api::exports::http };
+        let ruma_serde = quote! { #ruma_api::exports::ruma_serde };
+
+        let error_ty = &self.error_ty;
+        let init_fields = self
+            .fields
+            .iter()
+            .map(|request_field| {
+                let field = request_field.field();
+                let field_name =
+                    field.ident.as_ref().expect("expected field to have an identifier");
+                let cfg_attrs =
+                    field.attrs.iter().filter(|a| a.path.is_ident("cfg")).collect::<Vec<_>>();
+                let value = borrow_owned_field(&field.ty, quote! { self.#field_name })?;
+
+                Some(quote! {
+                    #( #cfg_attrs )*
+                    #field_name: #value,
+                })
+            })
+            .collect::<Option<TokenStream>>()?;
+
+        let non_auth_impl = matches!(self.authentication, AuthScheme::None(_)).then(|| {
+            quote! {
+                #[automatically_derived]
+                #[cfg(feature = "client")]
+                impl #ruma_api::OutgoingNonAuthRequest for IncomingRequest {}
+            }
+        });
+
+        Some(quote! {
+            #[automatically_derived]
+            #[cfg(feature = "client")]
+            impl #ruma_api::OutgoingRequest for IncomingRequest {
+                type EndpointError = #error_ty;
+                type IncomingResponse = <Response as #ruma_serde::Outgoing>::Incoming;
+
+                const METADATA: #ruma_api::Metadata = self::METADATA;
+
+                fn try_into_http_request<T: ::std::default::Default + #bytes::BufMut>(
+                    self,
+                    base_url: &::std::primitive::str,
+                    access_token: #ruma_api::SendAccessToken<'_>,
+                ) -> ::std::result::Result<#http::Request<T>, #ruma_api::error::IntoHttpError> {
+                    let request = Request { #init_fields };
+                    #ruma_api::OutgoingRequest::try_into_http_request(
+                        request,
+                        base_url,
+                        access_token,
+                    )
+                }
+            }
+
+            #non_auth_impl
+        })
     }
 
     /// Produces code for a struct initializer for the given field kind to be accessed through the
@@ -251,3 +332,54 @@ impl Request {
             .collect()
     }
 }
+
+/// Produces an expression that converts `src`, a field of `IncomingRequest`, to the field type
+/// `ty` of `Request`, or `None` if the conversion isn't supported.
+fn borrow_owned_field(ty: &Type, src: TokenStream) -> Option<TokenStream> {
+    fn has_lifetimes(ty: &Type) -> bool {
+        let mut lifetimes = BTreeSet::new();
+        collect_lifetime_idents(&mut lifetimes, ty);
+        !lifetimes.is_empty()
+    }
+
+    fn referenced_type(ty: &Type) -> Option<&Type> {
+        match ty {
+            Type::Reference(r) if r.mutability.is_none() && !has_lifetimes(&r.elem) => {
+                Some(&r.elem)
+            }
+            _ => None,
+        }
+    }
+
+    if !has_lifetimes(ty) {
+        return Some(src);
+    }
+
+    // `&'a T` is borrowed from the owned field through deref coercion, e.g. `&String` -> `&str`.
+    if referenced_type(ty).is_some() {
+        return Some(quote! { &#src });
+    }
+
+    // `Option<&'a T>`
+    let seg = match ty {
+        Type::Path(syn::TypePath { path, qself: None }) => path.segments.last()?,
+        _ => return None,
+    };
+    if seg.ident != "Option" {
+        return None;
+    }
+    let elem = match &seg.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => referenced_type(ty)?,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(quote! {
+        match &#src {
+            ::std::option::Option::Some(v) => ::std::option::Option::Some::<&#elem>(v),
+            ::std::option::Option::None => ::std::option::Option::None,
+        }
+    })
+}
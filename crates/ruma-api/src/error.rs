@@ -241,6 +241,12 @@ impl From<std::convert::Infallible> for DeserializationError {
     }
 }
 
+impl From<ruma_identifiers::ServerNameError> for DeserializationError {
+    fn from(err: ruma_identifiers::ServerNameError) -> Self {
+        Self::Ident(err.into())
+    }
+}
+
 impl From<http::header::ToStrError> for DeserializationError {
     fn from(err: http::header::ToStrError) -> Self {
         Self::Header(HeaderDeserializationError::ToStrError(err))
@@ -98,6 +98,11 @@ use ruma_identifiers::UserId;
 /// Any field that does not include one of these attributes will be part of the request's JSON
 /// body.
 ///
+/// If the request has fields with lifetimes, an owned `IncomingRequest` type is generated as
+/// well. When all of those fields are references or optional references, like `&'a UserId` or
+/// `Option<&'a str>`, `IncomingRequest` can also be sent as a request, which is useful when
+/// the request has to outlive the data it would otherwise borrow.
+///
 /// ## Response
 ///
 /// Like the request block, the response block consists of normal struct field definitions.
@@ -113,6 +118,10 @@ use ruma_identifiers::UserId;
 /// Any field that does not include the above attribute will be expected in the response's JSON
 /// body.
 ///
+/// If the response block is empty, the generated `Response` type implements `Default` and has
+/// a `new` constructor, so endpoints that don't return any data only need `response: {}`. Such
+/// a response is serialized as `{}`, and the body of an incoming response is ignored.
+///
 /// ## Newtype bodies
 ///
 /// Both the request and response block also support "newtype bodies" by using the
@@ -209,6 +218,18 @@ pub mod exports {
     pub use ruma_serde;
     pub use serde;
     pub use serde_json;
+
+    use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
+
+    /// The set of characters that are percent-encoded in path parameters.
+    ///
+    /// This is everything except the unreserved characters of [RFC 3986], with the exception of
+    /// `.`: it stays encoded so that a parameter of `.` or `..` can't turn into a dot-segment that
+    /// is removed when the path is normalized.
+    ///
+    /// [RFC 3986]: https://tools.ietf.org/html/rfc3986#section-2.3
+    pub const PATH_SEGMENT_ENCODE_SET: &AsciiSet =
+        &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'~');
 }
 
 use error::{FromHttpRequestError, FromHttpResponseError, IntoHttpError};
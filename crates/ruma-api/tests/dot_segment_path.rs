@@ -0,0 +1,40 @@
+#![allow(clippy::exhaustive_structs)]
+
+use ruma_api::{ruma_api, IncomingRequest as _, OutgoingRequest as _, SendAccessToken};
+
+ruma_api! {
+    metadata: {
+        description: "Does something with a transaction ID.",
+        method: PUT,
+        name: "dot_segment_path",
+        path: "/_matrix/foo/:room_id/send/:txn_id",
+        rate_limited: false,
+        authentication: None,
+    }
+
+    request: {
+        #[ruma_api(path)]
+        pub room_id: &'a str,
+        #[ruma_api(path)]
+        pub txn_id: &'a str,
+    }
+
+    response: {}
+}
+
+#[test]
+fn dot_segments_are_encoded() {
+    let req = Request { room_id: ".", txn_id: ".." };
+
+    // A literal `.` or `..` segment would be removed when the path is normalized.
+    assert_eq!(req.path(), "/_matrix/foo/%2E/send/%2E%2E");
+
+    let http_req = req
+        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None)
+        .unwrap();
+    assert_eq!(http_req.uri().path(), "/_matrix/foo/%2E/send/%2E%2E");
+
+    let req = IncomingRequest::try_from_http_request(http_req).unwrap();
+    assert_eq!(req.room_id, ".");
+    assert_eq!(req.txn_id, "..");
+}
@@ -1,4 +1,6 @@
-use ruma_api::{ruma_api, OutgoingRequest as _, OutgoingResponse as _, SendAccessToken};
+use ruma_api::{
+    ruma_api, IncomingResponse as _, OutgoingRequest as _, OutgoingResponse as _, SendAccessToken,
+};
 
 ruma_api! {
     metadata: {
@@ -31,3 +33,21 @@ fn empty_response_http_repr() {
 
     assert_eq!(http_res.body(), b"{}");
 }
+
+#[test]
+fn empty_response_constructors() {
+    let http_res = Response::new().try_into_http_response::<Vec<u8>>().unwrap();
+    assert_eq!(http_res.body(), b"{}");
+
+    let http_res = Response::default().try_into_http_response::<Vec<u8>>().unwrap();
+    assert_eq!(http_res.body(), b"{}");
+}
+
+#[test]
+fn empty_response_from_http() {
+    let http_res = http::Response::new(b"{}".to_vec());
+    Response::try_from_http_response(http_res).unwrap();
+
+    let http_res = http::Response::new(br#"{ "unexpected": "field" }"#.to_vec());
+    Response::try_from_http_response(http_res).unwrap();
+}
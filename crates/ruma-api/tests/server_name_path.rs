@@ -0,0 +1,53 @@
+#![allow(clippy::exhaustive_structs)]
+
+use std::convert::TryFrom;
+
+use ruma_api::{ruma_api, IncomingRequest as _, OutgoingRequest as _, SendAccessToken};
+use ruma_identifiers::ServerName;
+
+ruma_api! {
+    metadata: {
+        description: "Does something with a server name.",
+        method: GET,
+        name: "server_name_path",
+        path: "/_matrix/foo/:server_name/:media_id",
+        rate_limited: false,
+        authentication: None,
+    }
+
+    request: {
+        #[ruma_api(path)]
+        pub server_name: &'a ServerName,
+        #[ruma_api(path)]
+        pub media_id: &'a str,
+    }
+
+    response: {}
+}
+
+#[test]
+fn dns_name_path() {
+    let server_name = <&ServerName>::try_from("example.com:8448").unwrap();
+    let req = Request { server_name, media_id: "abc" };
+
+    assert_eq!(req.path(), "/_matrix/foo/example%2Ecom%3A8448/abc");
+}
+
+#[test]
+fn ipv6_literal_path() {
+    let server_name = <&ServerName>::try_from("[::1]:8448").unwrap();
+    let req = Request { server_name, media_id: "abc" };
+
+    // `[` and `]` are not allowed in a path segment, so they have to be encoded.
+    assert_eq!(req.path(), "/_matrix/foo/%5B%3A%3A1%5D%3A8448/abc");
+
+    let http_req = req
+        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None)
+        .unwrap();
+    assert_eq!(http_req.uri().path(), "/_matrix/foo/%5B%3A%3A1%5D%3A8448/abc");
+
+    let req = IncomingRequest::try_from_http_request(http_req).unwrap();
+    assert_eq!(*req.server_name, *server_name);
+    assert_eq!(req.server_name.host(), "[::1]");
+    assert_eq!(req.media_id, "abc");
+}
@@ -25,7 +25,6 @@ ruma_api! {
         pub events: &'a [Raw<AnyRoomEvent>],
     }
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -89,13 +88,6 @@ impl IncomingRequest {
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
 #[cfg(feature = "helper")]
 #[cfg(test)]
 mod helper_tests {
@@ -19,7 +19,6 @@ ruma_api! {
         pub room_alias: &'a RoomAliasId,
     }
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -29,10 +28,3 @@ impl<'a> Request<'a> {
         Self { room_alias }
     }
 }
-
-impl Response {
-    /// Create an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -19,7 +19,6 @@ ruma_api! {
         pub user_id: &'a UserId,
     }
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -29,10 +28,3 @@ impl<'a> Request<'a> {
         Self { user_id }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -26,7 +26,6 @@ ruma_api! {
         pub sid: &'a str,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: UiaaResponse
@@ -38,10 +37,3 @@ impl<'a> Request<'a> {
         Self { auth: None, client_secret, sid }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -27,7 +27,6 @@ ruma_api! {
         pub sid: &'a str,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -44,10 +43,3 @@ impl<'a> Request<'a> {
         Self { client_secret, identity_server_info, sid }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
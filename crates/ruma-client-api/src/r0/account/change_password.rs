@@ -33,7 +33,6 @@ ruma_api! {
         pub auth: Option<AuthData<'a>>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: UiaaResponse
@@ -45,10 +44,3 @@ impl<'a> Request<'a> {
         Self { new_password, logout_devices: true, auth: None }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
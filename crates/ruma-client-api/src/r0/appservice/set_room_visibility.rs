@@ -28,7 +28,6 @@ ruma_api! {
         pub visibility: Visibility,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -40,10 +39,3 @@ impl<'a> Request<'a> {
         Self { network_id, room_id, visibility }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
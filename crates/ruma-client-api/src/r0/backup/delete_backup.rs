@@ -18,7 +18,6 @@ ruma_api! {
         pub version: &'a str,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -30,10 +29,3 @@ impl<'a> Request<'a> {
         Self { version }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -24,7 +24,6 @@ ruma_api! {
         pub algorithm: BackupAlgorithm,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -36,10 +35,3 @@ impl<'a> Request<'a> {
         Self { version, algorithm }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
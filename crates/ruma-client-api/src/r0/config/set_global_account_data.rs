@@ -34,7 +34,6 @@ ruma_api! {
         pub data: &'a RawJsonValue,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -46,10 +45,3 @@ impl<'a> Request<'a> {
         Self { user_id, event_type, data }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -38,7 +38,6 @@ ruma_api! {
         pub user_id: &'a UserId,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -55,10 +54,3 @@ impl<'a> Request<'a> {
         Self { data, event_type, room_id, user_id }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
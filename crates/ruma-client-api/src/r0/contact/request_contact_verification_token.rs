@@ -41,7 +41,6 @@ ruma_api! {
         pub id_access_token: Option<&'a str>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -60,10 +59,3 @@ impl<'a> Request<'a> {
         }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -25,7 +25,6 @@ ruma_api! {
         pub auth: Option<AuthData<'a>>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: UiaaResponse
@@ -37,10 +36,3 @@ impl<'a> Request<'a> {
         Self { device_id, auth: None }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
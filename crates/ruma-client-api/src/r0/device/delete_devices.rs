@@ -24,7 +24,6 @@ ruma_api! {
         pub auth: Option<AuthData<'a>>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: UiaaResponse
@@ -36,10 +35,3 @@ impl<'a> Request<'a> {
         Self { devices, auth: None }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -24,7 +24,6 @@ ruma_api! {
         pub display_name: Option<String>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -36,10 +35,3 @@ impl<'a> Request<'a> {
         Self { device_id, display_name: None }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
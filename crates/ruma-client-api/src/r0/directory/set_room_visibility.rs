@@ -24,7 +24,6 @@ ruma_api! {
         pub visibility: Visibility,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -36,10 +35,3 @@ impl<'a> Request<'a> {
         Self { room_id, visibility }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
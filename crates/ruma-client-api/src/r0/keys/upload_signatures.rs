@@ -24,7 +24,6 @@ ruma_api! {
         pub signed_keys: BTreeMap<UserId, BTreeMap<String, JsonValue>>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -36,10 +35,3 @@ impl Request {
         Self { signed_keys }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -38,7 +38,6 @@ ruma_api! {
         pub user_signing_key: Option<CrossSigningKey>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: UiaaResponse
@@ -50,10 +49,3 @@ impl Request<'_> {
         Default::default()
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
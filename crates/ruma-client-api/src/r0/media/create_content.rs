@@ -32,8 +32,8 @@ ruma_api! {
         /// This uses the unstable prefix in
         /// [MSC2448](https://github.com/matrix-org/matrix-doc/pull/2448).
         #[ruma_api(query)]
-        #[cfg(feature = "unstable-pre-spec")]
-        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-pre-spec")))]
+        #[cfg(feature = "unstable-msc2448")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-msc2448")))]
         #[serde(rename = "xyz.amorgan.blurhash")]
         pub generate_blurhash: bool,
     }
@@ -46,8 +46,8 @@ ruma_api! {
         ///
         /// This uses the unstable prefix in
         /// [MSC2448](https://github.com/matrix-org/matrix-doc/pull/2448).
-        #[cfg(feature = "unstable-pre-spec")]
-        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-pre-spec")))]
+        #[cfg(feature = "unstable-msc2448")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-msc2448")))]
         #[serde(rename = "xyz.amorgan.blurhash")]
         #[serde(skip_serializing_if = "Option::is_none")]
         pub blurhash: Option<String>,
@@ -63,7 +63,7 @@ impl<'a> Request<'a> {
             file,
             filename: None,
             content_type: None,
-            #[cfg(feature = "unstable-pre-spec")]
+            #[cfg(feature = "unstable-msc2448")]
             generate_blurhash: false,
         }
     }
@@ -74,7 +74,7 @@ impl Response {
     pub fn new(content_uri: MxcUri) -> Self {
         Self {
             content_uri,
-            #[cfg(feature = "unstable-pre-spec")]
+            #[cfg(feature = "unstable-msc2448")]
             blurhash: None,
         }
     }
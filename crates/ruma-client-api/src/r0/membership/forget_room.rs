@@ -19,7 +19,6 @@ ruma_api! {
         pub room_id: &'a RoomId,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -31,10 +30,3 @@ impl<'a> Request<'a> {
         Self { room_id }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -34,7 +34,6 @@ ruma_api! {
         pub recipient: InvitationRecipient<'a>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -47,13 +46,6 @@ impl<'a> Request<'a> {
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
 /// Distinguishes between invititations by Matrix or third party identifiers.
 #[derive(Clone, Debug, PartialEq, Outgoing, Serialize)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
@@ -26,7 +26,6 @@ ruma_api! {
         pub reason: Option<&'a str>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -38,10 +37,3 @@ impl<'a> Request<'a> {
         Self { room_id, user_id, reason: None }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
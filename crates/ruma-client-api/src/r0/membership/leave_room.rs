@@ -25,7 +25,6 @@ ruma_api! {
         pub reason: Option<&'a str>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -41,10 +40,3 @@ impl<'a> Request<'a> {
         }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
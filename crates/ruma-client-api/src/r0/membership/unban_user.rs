@@ -22,7 +22,6 @@ ruma_api! {
         pub user_id: &'a UserId,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -34,10 +33,3 @@ impl<'a> Request<'a> {
         Self { room_id, user_id }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -27,7 +27,6 @@ ruma_api! {
         pub status_msg: Option<&'a str>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -39,10 +38,3 @@ impl<'a> Request<'a> {
         Self { user_id, presence, status_msg: None }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -36,8 +36,8 @@ ruma_api! {
         ///
         /// This uses the unstable prefix in
         /// [MSC2448](https://github.com/matrix-org/matrix-doc/pull/2448).
-        #[cfg(feature = "unstable-pre-spec")]
-        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-pre-spec")))]
+        #[cfg(feature = "unstable-msc2448")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-msc2448")))]
         #[serde(rename = "xyz.amorgan.blurhash")]
         #[serde(skip_serializing_if = "Option::is_none")]
         pub blurhash: Option<String>,
@@ -58,7 +58,7 @@ impl Response {
     pub fn new(avatar_url: Option<MxcUri>) -> Self {
         Self {
             avatar_url,
-            #[cfg(feature = "unstable-pre-spec")]
+            #[cfg(feature = "unstable-msc2448")]
             blurhash: None,
         }
     }
@@ -1,5 +1,7 @@
 //! [GET /_matrix/client/r0/profile/{userId}](https://matrix.org/docs/spec/client_server/r0.6.0#get-matrix-client-r0-profile-userid)
 
+use std::collections::BTreeMap;
+
 use ruma_api::ruma_api;
 use ruma_identifiers::{MxcUri, UserId};
 
@@ -40,8 +42,8 @@ ruma_api! {
         ///
         /// This uses the unstable prefix in
         /// [MSC2448](https://github.com/matrix-org/matrix-doc/pull/2448).
-        #[cfg(feature = "unstable-pre-spec")]
-        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-pre-spec")))]
+        #[cfg(feature = "unstable-msc2448")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-msc2448")))]
         #[serde(rename = "xyz.amorgan.blurhash")]
         #[serde(skip_serializing_if = "Option::is_none")]
         pub blurhash: Option<String>,
@@ -52,9 +54,32 @@ ruma_api! {
 
 impl<'a> Request<'a> {
     /// Creates a new `Request` with the given user ID.
+    ///
+    /// `Request` borrows the user ID, so it can't implement `Default`. If there is no user ID to
+    /// borrow from, e.g. in tests, use [`IncomingRequest::new`] instead, which takes an owned user
+    /// ID and can be sent the same way.
     pub fn new(user_id: &'a UserId) -> Self {
         Self { user_id }
     }
+
+    /// Creates one `Request` for each of the given user IDs, in the same order.
+    ///
+    /// There is no endpoint to get the profiles of several users at once, so every profile has to
+    /// be requested separately. The responses can be matched back up with the users using
+    /// [`Response::assemble_batch`].
+    pub fn batch(user_ids: &[&'a UserId]) -> Vec<Self> {
+        user_ids.iter().map(|user_id| Self::new(user_id)).collect()
+    }
+}
+
+impl IncomingRequest {
+    /// Creates a new `IncomingRequest` with the given user ID.
+    ///
+    /// Unlike `Request`, this owns the user ID, so it can be sent without keeping the user ID
+    /// alive elsewhere.
+    pub fn new(user_id: UserId) -> Self {
+        Self { user_id }
+    }
 }
 
 impl Response {
@@ -63,8 +88,308 @@ impl Response {
         Self {
             avatar_url,
             displayname,
-            #[cfg(feature = "unstable-pre-spec")]
+            #[cfg(feature = "unstable-msc2448")]
             blurhash: None,
         }
     }
+
+    /// Creates a new `Response` with the given avatar URL, display name and BlurHash.
+    #[cfg(feature = "unstable-msc2448")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable-msc2448")))]
+    pub fn with_blurhash(
+        avatar_url: Option<MxcUri>,
+        displayname: Option<String>,
+        blurhash: Option<String>,
+    ) -> Self {
+        Self { avatar_url, displayname, blurhash }
+    }
+
+    /// Sets the avatar URL of this `Response`.
+    pub fn with_avatar_url(mut self, avatar_url: Option<MxcUri>) -> Self {
+        self.avatar_url = avatar_url;
+        self
+    }
+
+    /// Sets the display name of this `Response`.
+    pub fn with_displayname(mut self, displayname: Option<String>) -> Self {
+        self.displayname = displayname;
+        self
+    }
+
+    /// Collects the results of sending the given requests, e.g. created by [`Request::batch`],
+    /// into a map from user ID to result.
+    ///
+    /// `results` must be in the same order as `requests`. If there are more requests than
+    /// results, the remaining requests are left out of the map, and vice versa. If a user ID
+    /// appears more than once, the last result for it is kept.
+    pub fn assemble_batch<'a, E>(
+        requests: &[Request<'a>],
+        results: impl IntoIterator<Item = Result<Response, E>>,
+    ) -> BTreeMap<&'a UserId, Result<Response, E>> {
+        requests.iter().map(|request| request.user_id).zip(results).collect()
+    }
+
+    /// Merges this `Response` into `base`, e.g. a previously cached profile.
+    ///
+    /// Fields that are `Some` in this response always replace the ones of `base`. Fields that are
+    /// `None` mean that the profile doesn't have that field (anymore) if `clear_missing` is true,
+    /// so they are cleared in `base`. Otherwise, they are treated as unchanged and `base` keeps
+    /// its value.
+    pub fn merge_into(self, base: &mut Response, clear_missing: bool) {
+        fn merge<T>(new: Option<T>, base: &mut Option<T>, clear_missing: bool) {
+            if new.is_some() || clear_missing {
+                *base = new;
+            }
+        }
+
+        merge(self.avatar_url, &mut base.avatar_url, clear_missing);
+        merge(self.displayname, &mut base.displayname, clear_missing);
+        #[cfg(feature = "unstable-msc2448")]
+        merge(self.blurhash, &mut base.blurhash, clear_missing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruma_identifiers::{user_id, MxcUri};
+
+    use super::{IncomingRequest, Request, Response};
+
+    #[test]
+    fn owned_request() {
+        let req = IncomingRequest::new(user_id!("@alice:example.com"));
+        assert_eq!(req.user_id, "@alice:example.com");
+
+        // A borrowed `Request` can still be created from it where one is needed.
+        let req = Request::new(&req.user_id);
+        assert_eq!(req.user_id, "@alice:example.com");
+    }
+
+    #[test]
+    fn batch() {
+        let alice = user_id!("@alice:example.com");
+        let bob = user_id!("@bob:example.com");
+        let carl = user_id!("@carl:example.org");
+
+        let requests = Request::batch(&[&alice, &bob, &carl]);
+        let user_ids: Vec<_> = requests.iter().map(|req| req.user_id).collect();
+        assert_eq!(user_ids, [&alice, &bob, &carl]);
+
+        let results = vec![
+            Ok(Response::new(None, Some("Alice".to_owned()))),
+            Err("M_NOT_FOUND"),
+            Ok(Response::new(Some(MxcUri::from("mxc://example.org/carl")), None)),
+        ];
+        let profiles = Response::assemble_batch(&requests, results);
+
+        assert_eq!(profiles.len(), 3);
+        assert_eq!(profiles[&alice].as_ref().unwrap().displayname.as_deref(), Some("Alice"));
+        assert_eq!(profiles[&bob].as_ref().unwrap_err(), &"M_NOT_FOUND");
+        assert_eq!(
+            profiles[&carl].as_ref().unwrap().avatar_url,
+            Some(MxcUri::from("mxc://example.org/carl"))
+        );
+    }
+
+    #[test]
+    fn with_methods() {
+        let response = Response::default()
+            .with_avatar_url(Some(MxcUri::from("mxc://matrix.org/abc123")))
+            .with_displayname(Some("Alice".to_owned()));
+
+        assert_eq!(response.avatar_url, Some(MxcUri::from("mxc://matrix.org/abc123")));
+        assert_eq!(response.displayname.as_deref(), Some("Alice"));
+
+        let response = response.with_avatar_url(None);
+        assert_eq!(response.avatar_url, None);
+        assert_eq!(response.displayname.as_deref(), Some("Alice"));
+    }
+
+    #[test]
+    fn merge_into_keeps_missing() {
+        let mut base =
+            Response::new(Some(MxcUri::from("mxc://matrix.org/abc123")), Some("Alice".to_owned()));
+
+        Response::new(None, Some("Bob".to_owned())).merge_into(&mut base, false);
+        assert_eq!(base.avatar_url, Some(MxcUri::from("mxc://matrix.org/abc123")));
+        assert_eq!(base.displayname.as_deref(), Some("Bob"));
+
+        Response::default().merge_into(&mut base, false);
+        assert_eq!(base.avatar_url, Some(MxcUri::from("mxc://matrix.org/abc123")));
+        assert_eq!(base.displayname.as_deref(), Some("Bob"));
+    }
+
+    #[test]
+    fn merge_into_clears_missing() {
+        let mut base =
+            Response::new(Some(MxcUri::from("mxc://matrix.org/abc123")), Some("Alice".to_owned()));
+
+        Response::new(None, Some("Bob".to_owned())).merge_into(&mut base, true);
+        assert_eq!(base.avatar_url, None);
+        assert_eq!(base.displayname.as_deref(), Some("Bob"));
+
+        Response::default().merge_into(&mut base, true);
+        assert_eq!(base.avatar_url, None);
+        assert_eq!(base.displayname, None);
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod client_tests {
+    use std::convert::TryFrom;
+
+    use ruma_api::{OutgoingRequest as _, SendAccessToken};
+    use ruma_identifiers::UserId;
+
+    use super::{IncomingRequest, Request};
+
+    #[test]
+    fn serialize_request_with_access_token() {
+        let user_id = UserId::try_from("@alice:example.com").unwrap();
+        let req: http::Request<Vec<u8>> = Request::new(&user_id)
+            .try_into_http_request("https://homeserver.tld", SendAccessToken::Always("auth_tok"))
+            .unwrap();
+
+        assert_eq!(req.uri().path(), "/_matrix/client/r0/profile/%40alice%3Aexample%2Ecom");
+        assert_eq!(req.headers().get(http::header::AUTHORIZATION).unwrap(), "Bearer auth_tok");
+    }
+
+    #[test]
+    fn request_path() {
+        let user_id = UserId::try_from("@carl:example.com").unwrap();
+
+        assert_eq!(
+            Request::new(&user_id).path(),
+            "/_matrix/client/r0/profile/%40carl%3Aexample%2Ecom"
+        );
+    }
+
+    #[test]
+    fn serialize_request_without_access_token() {
+        let user_id = UserId::try_from("@alice:example.com").unwrap();
+        let req: http::Request<Vec<u8>> = Request::new(&user_id)
+            .try_into_http_request(
+                "https://homeserver.tld",
+                SendAccessToken::IfRequired("auth_tok"),
+            )
+            .unwrap();
+
+        assert!(req.headers().get(http::header::AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn serialize_owned_request() {
+        fn assert_static<T: 'static>(_: &T) {}
+
+        let req = IncomingRequest::new(UserId::try_from("@alice:example.com").unwrap());
+        assert_static(&req);
+
+        let req: http::Request<Vec<u8>> =
+            req.try_into_http_request("https://homeserver.tld", SendAccessToken::None).unwrap();
+        assert_eq!(req.uri().path(), "/_matrix/client/r0/profile/%40alice%3Aexample%2Ecom");
+    }
+
+    #[test]
+    fn deserialize_error_response() {
+        use matches::assert_matches;
+        use ruma_api::{
+            error::{FromHttpResponseError, ServerError},
+            IncomingResponse as _,
+        };
+
+        use super::Response;
+        use crate::error::{Error, ErrorKind};
+
+        let http_response = http::Response::builder()
+            .status(http::StatusCode::NOT_FOUND)
+            .body(br#"{ "errcode": "M_NOT_FOUND", "error": "Profile not found" }"#.to_vec())
+            .unwrap();
+        assert_matches!(
+            Response::try_from_http_response(http_response),
+            Err(FromHttpResponseError::Http(ServerError::Known(Error {
+                kind: ErrorKind::NotFound,
+                status_code: http::StatusCode::NOT_FOUND,
+                ..
+            })))
+        );
+
+        let http_response = http::Response::builder()
+            .status(http::StatusCode::FORBIDDEN)
+            .body(br#"{ "errcode": "M_FORBIDDEN", "error": "Profile lookup forbidden" }"#.to_vec())
+            .unwrap();
+        assert_matches!(
+            Response::try_from_http_response(http_response),
+            Err(FromHttpResponseError::Http(ServerError::Known(Error {
+                kind: ErrorKind::Forbidden,
+                status_code: http::StatusCode::FORBIDDEN,
+                ..
+            })))
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn path_parameter_roundtrip() {
+        use ruma_api::IncomingRequest as _;
+
+        let user_id = UserId::try_from("@a_b-c~d:b.com").unwrap();
+        let req: http::Request<Vec<u8>> = Request::new(&user_id)
+            .try_into_http_request("https://homeserver.tld", SendAccessToken::None)
+            .unwrap();
+        assert_eq!(req.uri().path(), "/_matrix/client/r0/profile/%40a_b-c~d%3Ab%2Ecom");
+
+        let req = IncomingRequest::try_from_http_request(req).unwrap();
+        assert_eq!(req.user_id, user_id);
+    }
+
+    #[cfg(all(feature = "server", feature = "unstable-msc2448"))]
+    #[test]
+    fn blurhash_roundtrip() {
+        use ruma_api::{IncomingResponse as _, OutgoingResponse as _};
+        use serde_json::{
+            from_slice as from_json_slice, json, to_vec as to_json_vec, Value as JsonValue,
+        };
+
+        use super::Response;
+
+        let json = json!({
+            "avatar_url": "mxc://matrix.org/abc123",
+            "displayname": "Alice",
+            "xyz.amorgan.blurhash": "LEHV6nWB2yk8pyo0adR*.7kCMdnj",
+        });
+        let http_response = http::Response::new(to_json_vec(&json).unwrap());
+
+        let response = Response::try_from_http_response(http_response).unwrap();
+        assert_eq!(response.blurhash.as_deref(), Some("LEHV6nWB2yk8pyo0adR*.7kCMdnj"));
+
+        let http_response = response.try_into_http_response::<Vec<u8>>().unwrap();
+        assert_eq!(from_json_slice::<JsonValue>(http_response.body()).unwrap(), json);
+    }
+}
+
+#[cfg(all(test, feature = "server", feature = "unstable-msc2448"))]
+mod server_tests {
+    use ruma_api::OutgoingResponse as _;
+    use ruma_identifiers::MxcUri;
+    use serde_json::{from_slice as from_json_slice, json, Value as JsonValue};
+
+    use super::Response;
+
+    #[test]
+    fn serialize_response_with_blurhash() {
+        let response = Response::with_blurhash(
+            Some(MxcUri::from("mxc://matrix.org/abc123")),
+            None,
+            Some("LEHV6nWB2yk8pyo0adR*.7kCMdnj".to_owned()),
+        );
+        let http_response = response.try_into_http_response::<Vec<u8>>().unwrap();
+
+        assert_eq!(
+            from_json_slice::<JsonValue>(http_response.body()).unwrap(),
+            json!({
+                "avatar_url": "mxc://matrix.org/abc123",
+                "xyz.amorgan.blurhash": "LEHV6nWB2yk8pyo0adR*.7kCMdnj",
+            })
+        );
+    }
 }
@@ -42,14 +42,13 @@ ruma_api! {
         ///
         /// This uses the unstable prefix in
         /// [MSC2448](https://github.com/matrix-org/matrix-doc/pull/2448).
-        #[cfg(feature = "unstable-pre-spec")]
-        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-pre-spec")))]
+        #[cfg(feature = "unstable-msc2448")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "unstable-msc2448")))]
         #[serde(rename = "xyz.amorgan.blurhash")]
         #[serde(skip_serializing_if = "Option::is_none")]
         pub blurhash: Option<&'a str>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -61,16 +60,59 @@ impl<'a> Request<'a> {
         Self {
             user_id,
             avatar_url,
-            #[cfg(feature = "unstable-pre-spec")]
+            #[cfg(feature = "unstable-msc2448")]
             blurhash: None,
         }
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
+#[cfg(all(test, feature = "client"))]
+mod client_tests {
+    use std::convert::TryFrom;
+
+    use ruma_api::{OutgoingRequest as _, SendAccessToken};
+    use ruma_identifiers::{MxcUri, UserId};
+    use serde_json::{from_slice as from_json_slice, json, Value as JsonValue};
+
+    use super::Request;
+
+    #[test]
+    fn serialize_request() {
+        let user_id = UserId::try_from("@alice:example.com").unwrap();
+        let avatar_url = MxcUri::from("mxc://example.com/abc123");
+        let req: http::Request<Vec<u8>> = Request::new(&user_id, Some(&avatar_url))
+            .try_into_http_request(
+                "https://homeserver.tld",
+                SendAccessToken::IfRequired("auth_tok"),
+            )
+            .unwrap();
+
+        assert_eq!(req.method(), http::Method::PUT);
+        assert_eq!(
+            req.uri().path(),
+            "/_matrix/client/r0/profile/%40alice%3Aexample%2Ecom/avatar_url"
+        );
+        assert_eq!(req.headers().get(http::header::AUTHORIZATION).unwrap(), "Bearer auth_tok");
+        assert_eq!(
+            from_json_slice::<JsonValue>(req.body()).unwrap(),
+            json!({ "avatar_url": "mxc://example.com/abc123" })
+        );
+    }
+
+    #[test]
+    fn serialize_unset_request() {
+        let user_id = UserId::try_from("@alice:example.com").unwrap();
+        let req: http::Request<Vec<u8>> = Request::new(&user_id, None)
+            .try_into_http_request(
+                "https://homeserver.tld",
+                SendAccessToken::IfRequired("auth_tok"),
+            )
+            .unwrap();
+
+        #[cfg(not(feature = "compat"))]
+        assert_eq!(from_json_slice::<JsonValue>(req.body()).unwrap(), json!({}));
+        #[cfg(feature = "compat")]
+        assert_eq!(from_json_slice::<JsonValue>(req.body()).unwrap(), json!({ "avatar_url": "" }));
     }
 }
 
@@ -92,8 +134,11 @@ mod tests {
             ).unwrap(),
             IncomingRequest { user_id, avatar_url: None, .. } if user_id == "@foo:bar.org"
         );
+    }
 
-        #[cfg(feature = "compat")]
+    #[cfg(feature = "compat")]
+    #[test]
+    fn deserialize_empty_string_request() {
         assert_matches!(
             IncomingRequest::try_from_http_request(
                 http::Request::builder()
@@ -23,7 +23,6 @@ ruma_api! {
         pub displayname: Option<&'a str>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -36,9 +35,48 @@ impl<'a> Request<'a> {
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ruma_api::{OutgoingRequest as _, SendAccessToken};
+    use ruma_identifiers::UserId;
+    use serde_json::{from_slice as from_json_slice, json, Value as JsonValue};
+
+    use super::Request;
+
+    #[test]
+    fn serialize_request() {
+        let user_id = UserId::try_from("@alice:example.com").unwrap();
+        let req: http::Request<Vec<u8>> = Request::new(&user_id, Some("Alice"))
+            .try_into_http_request(
+                "https://homeserver.tld",
+                SendAccessToken::IfRequired("auth_tok"),
+            )
+            .unwrap();
+
+        assert_eq!(req.method(), http::Method::PUT);
+        assert_eq!(
+            req.uri().path(),
+            "/_matrix/client/r0/profile/%40alice%3Aexample%2Ecom/displayname"
+        );
+        assert_eq!(req.headers().get(http::header::AUTHORIZATION).unwrap(), "Bearer auth_tok");
+        assert_eq!(
+            from_json_slice::<JsonValue>(req.body()).unwrap(),
+            json!({ "displayname": "Alice" })
+        );
+    }
+
+    #[test]
+    fn serialize_request_without_display_name() {
+        let user_id = UserId::try_from("@alice:example.com").unwrap();
+        let req: http::Request<Vec<u8>> = Request::new(&user_id, None)
+            .try_into_http_request(
+                "https://homeserver.tld",
+                SendAccessToken::IfRequired("auth_tok"),
+            )
+            .unwrap();
+
+        assert_eq!(from_json_slice::<JsonValue>(req.body()).unwrap(), json!({}));
     }
 }
@@ -28,7 +28,6 @@ ruma_api! {
         pub rule_id: &'a str,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -40,10 +39,3 @@ impl<'a> Request<'a> {
         Self { scope, kind, rule_id }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -27,7 +27,6 @@ ruma_api! {
         pub append: bool,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -40,13 +39,6 @@ impl Request {
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
 /// Defines a pusher.
 ///
 /// To create an instance of this type, first create a `PusherInit` and convert it via
@@ -52,7 +52,6 @@ ruma_api! {
         pub pattern: Option<&'a str>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -73,10 +72,3 @@ impl<'a> Request<'a> {
         }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -32,7 +32,6 @@ ruma_api! {
         pub actions: Vec<Action>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -44,10 +43,3 @@ impl<'a> Request<'a> {
         Self { scope, kind, rule_id, actions }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -31,7 +31,6 @@ ruma_api! {
         pub enabled: bool,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -53,10 +52,3 @@ impl<'a> Request<'a> {
         Self::new(scope, kind, rule_id, false)
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
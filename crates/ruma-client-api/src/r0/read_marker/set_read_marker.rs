@@ -30,7 +30,6 @@ ruma_api! {
         pub read_receipt: Option<&'a EventId>,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -42,10 +41,3 @@ impl<'a> Request<'a> {
         Self { room_id, fully_read, read_receipt: None }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -28,7 +28,6 @@ ruma_api! {
         pub event_id: &'a EventId,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -40,10 +39,3 @@ impl<'a> Request<'a> {
         Self { room_id, receipt_type, event_id }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
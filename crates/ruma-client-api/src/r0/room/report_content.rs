@@ -30,7 +30,6 @@ ruma_api! {
         pub reason: &'a str,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -42,10 +41,3 @@ impl<'a> Request<'a> {
         Self { room_id, event_id, score, reason }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -32,7 +32,6 @@ ruma_api! {
         pub tag_info: TagInfo,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -44,10 +43,3 @@ impl<'a> Request<'a> {
         Self { user_id, room_id, tag, tag_info }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
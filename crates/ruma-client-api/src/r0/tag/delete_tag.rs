@@ -27,7 +27,6 @@ ruma_api! {
         pub tag: &'a str,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -39,10 +38,3 @@ impl<'a> Request<'a> {
         Self { user_id, room_id, tag }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
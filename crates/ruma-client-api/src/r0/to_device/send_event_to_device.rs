@@ -34,7 +34,6 @@ ruma_api! {
         pub messages: Messages,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -47,13 +46,6 @@ impl<'a> Request<'a> {
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
 /// Messages to send in a send-to-device request.
 ///
 /// Represented as a map of `{ user-ids => { device-ids => message-content } }`.
@@ -30,7 +30,6 @@ ruma_api! {
         pub state: Typing,
     }
 
-    #[derive(Default)]
     response: {}
 
     error: crate::Error
@@ -43,13 +42,6 @@ impl<'a> Request<'a> {
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
 /// A mark for whether the user is typing within a length of time or not.
 #[derive(Clone, Copy, Debug, Serialize)]
 #[serde(into = "TypingInner")]
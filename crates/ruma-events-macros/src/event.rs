@@ -67,6 +67,7 @@ fn expand_serialize_event(
 
     let ident = &input.ident;
     let (impl_gen, ty_gen, where_clause) = input.generics.split_for_impl();
+    let is_generic = !input.generics.params.is_empty();
     let serialize_fields: Vec<_> = fields
         .iter()
         .map(|field| {
@@ -89,6 +90,18 @@ fn expand_serialize_event(
                         state.serialize_field("unsigned", &self.unsigned)?;
                     }
                 }
+            } else if name == "state_key" && is_generic && !var.is_redacted() {
+                quote! {
+                    if #ruma_events::StateEventContent::requires_empty_state_key(&self.content)
+                        && !self.state_key.is_empty()
+                    {
+                        return Err(S::Error::custom(format!(
+                            "`{}` events must have an empty state key",
+                            event_type,
+                        )));
+                    }
+                    state.serialize_field("state_key", &self.state_key)?;
+                }
             } else {
                 quote! {
                     state.serialize_field(stringify!(#name), &self.#name)?;
@@ -235,7 +248,7 @@ fn expand_deserialize_event(
                     )
                 });
 
-                if has_default_attr {
+                let unwrap_field = if has_default_attr {
                     quote! {
                         let #name = #name.unwrap_or_default();
                     }
@@ -245,6 +258,23 @@ fn expand_deserialize_event(
                             #serde::de::Error::missing_field(stringify!(#name))
                         })?;
                     }
+                };
+
+                if name == "state_key" && is_generic && !var.is_redacted() {
+                    quote! {
+                        #unwrap_field
+
+                        if #ruma_events::StateEventContent::requires_empty_state_key(&content)
+                            && !state_key.is_empty()
+                        {
+                            return Err(#serde::de::Error::invalid_value(
+                                #serde::de::Unexpected::Str(&state_key),
+                                &"an empty state key",
+                            ));
+                        }
+                    }
+                } else {
+                    unwrap_field
                 }
             })
         })
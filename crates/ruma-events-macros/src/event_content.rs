@@ -4,7 +4,7 @@ use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::{
     parse::{Parse, ParseStream},
-    DeriveInput, Ident, LitStr, Token,
+    DeriveInput, Ident, Lit, LitStr, Meta, MetaNameValue, NestedMeta, Token,
 };
 
 use crate::event_parse::EventKind;
@@ -16,6 +16,8 @@ mod kw {
     syn::custom_keyword!(custom_redacted);
     // The kind of event content this is.
     syn::custom_keyword!(kind);
+    // State events with this content must have an empty state key.
+    syn::custom_keyword!(empty_state_key);
 }
 
 /// Parses attributes for `*EventContent` derives.
@@ -34,6 +36,9 @@ enum EventMeta {
     /// This attribute signals that the events redacted form is manually implemented and should
     /// not be generated.
     CustomRedacted,
+
+    /// State events with this content must have an empty state key.
+    EmptyStateKey,
 }
 
 impl EventMeta {
@@ -71,6 +76,9 @@ impl Parse for EventMeta {
         } else if lookahead.peek(kw::custom_redacted) {
             let _: kw::custom_redacted = input.parse()?;
             Ok(EventMeta::CustomRedacted)
+        } else if lookahead.peek(kw::empty_state_key) {
+            let _: kw::empty_state_key = input.parse()?;
+            Ok(EventMeta::EmptyStateKey)
         } else {
             Err(lookahead.error())
         }
@@ -84,6 +92,10 @@ impl MetaAttrs {
         self.0.iter().any(|a| matches!(a, &EventMeta::CustomRedacted))
     }
 
+    fn has_empty_state_key(&self) -> bool {
+        self.0.iter().any(|a| matches!(a, &EventMeta::EmptyStateKey))
+    }
+
     fn get_event_type(&self) -> Option<&LitStr> {
         self.0.iter().find_map(|a| a.get_event_type())
     }
@@ -129,6 +141,14 @@ pub fn expand_event_content(
     let content_derives =
         content_attr.iter().flat_map(|args| args.get_event_kinds()).collect::<Vec<_>>();
 
+    let empty_state_key = content_attr.iter().any(|a| a.has_empty_state_key());
+    if empty_state_key && !content_derives.iter().any(|k| k.is_state()) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "`empty_state_key` can only be used with `kind = State`",
+        ));
+    }
+
     // We only generate redacted content structs for state and message events
     let redacted = if needs_redacted(&content_attr, &content_derives) {
         let doc = format!("The payload for a redacted `{}`", ident);
@@ -219,7 +239,7 @@ pub fn expand_event_content(
         };
 
         let redacted_event_content =
-            generate_event_content_impl(&redacted_ident, event_type, ruma_events);
+            generate_event_content_impl(&redacted_ident, event_type, None, ruma_events);
 
         let redacted_event_content_derive = content_derives
             .iter()
@@ -286,9 +306,11 @@ pub fn expand_event_content(
     };
 
     let event_content_derive =
-        generate_event_content_derives(&content_derives, ident, ruma_events)?;
+        generate_event_content_derives(&content_derives, empty_state_key, ident, ruma_events)?;
 
-    let event_content = generate_event_content_impl(ident, event_type, ruma_events);
+    let known_fields = known_fields(input);
+    let event_content =
+        generate_event_content_impl(ident, event_type, known_fields.as_deref(), ruma_events);
 
     Ok(quote! {
         #event_content
@@ -299,6 +321,7 @@ pub fn expand_event_content(
 
 fn generate_event_content_derives(
     content_attr: &[&EventKind],
+    empty_state_key: bool,
     ident: &Ident,
     ruma_events: &TokenStream,
 ) -> syn::Result<TokenStream> {
@@ -319,10 +342,21 @@ fn generate_event_content_derives(
         })
         .collect::<syn::Result<_>>()?;
 
+    let marker_trait_bodies = content_attr.iter().map(|kind| match kind {
+        EventKind::State if empty_state_key => quote! {
+            fn requires_empty_state_key(&self) -> bool {
+                true
+            }
+        },
+        _ => TokenStream::new(),
+    });
+
     Ok(quote! {
         #(
             #[automatically_derived]
-            impl #ruma_events::#marker_traits for #ident {}
+            impl #ruma_events::#marker_traits for #ident {
+                #marker_trait_bodies
+            }
         )*
     })
 }
@@ -330,11 +364,18 @@ fn generate_event_content_derives(
 fn generate_event_content_impl(
     ident: &Ident,
     event_type: &LitStr,
+    known_fields: Option<&[String]>,
     ruma_events: &TokenStream,
 ) -> TokenStream {
     let serde = quote! { #ruma_events::exports::serde };
     let serde_json = quote! { #ruma_events::exports::serde_json };
 
+    let deny_unknown_fields = known_fields.map(|known_fields| {
+        quote! {
+            #ruma_events::deny_unknown_content_fields(content, &[#( #known_fields ),*])?;
+        }
+    });
+
     quote! {
         #[automatically_derived]
         impl #ruma_events::EventContent for #ident {
@@ -352,12 +393,85 @@ fn generate_event_content_impl(
                     ));
                 }
 
+                #deny_unknown_fields
+
                 #serde_json::from_str(content.get())
             }
         }
     }
 }
 
+/// Collects the JSON keys that the `Deserialize` implementation of the content struct accepts.
+///
+/// Returns `None` if they can't be determined from the struct definition, e.g. because of a
+/// flattened field or a container attribute that changes the field names, in which case unknown
+/// fields are not rejected by the `unstable-strict-content` feature.
+fn known_fields(input: &DeriveInput) -> Option<Vec<String>> {
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) => fields,
+        _ => return None,
+    };
+
+    let serde_metas = |attrs: &[syn::Attribute]| -> Vec<Meta> {
+        attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("serde"))
+            .filter_map(|attr| match attr.parse_meta() {
+                Ok(Meta::List(list)) => Some(list.nested),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|nested| match nested {
+                NestedMeta::Meta(meta) => Some(meta),
+                NestedMeta::Lit(_) => None,
+            })
+            .collect()
+    };
+
+    let changes_field_names = serde_metas(&input.attrs).iter().any(|meta| {
+        ["rename_all", "transparent", "from", "try_from", "untagged", "tag"]
+            .iter()
+            .any(|name| meta.path().is_ident(name))
+    });
+    if changes_field_names {
+        return None;
+    }
+
+    let mut known_fields = Vec::new();
+    for field in &fields.named {
+        let mut name = field.ident.as_ref()?.to_string().trim_start_matches("r#").to_owned();
+        let mut aliases = Vec::new();
+
+        for meta in serde_metas(&field.attrs) {
+            match meta {
+                Meta::Path(path) if path.is_ident("flatten") => return None,
+                Meta::Path(path)
+                    if path.is_ident("skip") || path.is_ident("skip_deserializing") =>
+                {
+                    name.clear();
+                }
+                Meta::NameValue(MetaNameValue { path, lit: Lit::Str(lit), .. }) => {
+                    if path.is_ident("rename") {
+                        name = lit.value();
+                    } else if path.is_ident("alias") {
+                        aliases.push(lit.value());
+                    }
+                }
+                // `rename(deserialize = "...")` and friends are not supported.
+                Meta::List(list) if list.path.is_ident("rename") => return None,
+                _ => {}
+            }
+        }
+
+        if !name.is_empty() {
+            known_fields.push(name);
+            known_fields.extend(aliases);
+        }
+    }
+
+    Some(known_fields)
+}
+
 fn needs_redacted(input: &[MetaAttrs], content_derives: &[&EventKind]) -> bool {
     // `is_custom` means that the content struct does not need a generated
     // redacted struct also. If no `custom_redacted` attrs are found the content
@@ -429,7 +429,7 @@ fn expand_content_enum(
         }
     };
 
-    let marker_trait_impl = marker_trait(kind, ruma_events);
+    let marker_trait_impl = marker_trait(kind, &variant_arms, ruma_events);
 
     let redacted_content_enum = if kind.is_state() || kind.is_message() {
         let redacted_ident = kind.to_redacted_content_enum();
@@ -699,7 +699,11 @@ fn generate_custom_variant(
     }
 }
 
-fn marker_trait(kind: &EventKind, ruma_events: &TokenStream) -> TokenStream {
+fn marker_trait(
+    kind: &EventKind,
+    variant_arms: &[TokenStream],
+    ruma_events: &TokenStream,
+) -> TokenStream {
     let marker_trait = match kind {
         EventKind::State => quote! { StateEventContent },
         EventKind::Message => quote! { MessageEventContent },
@@ -710,10 +714,31 @@ fn marker_trait(kind: &EventKind, ruma_events: &TokenStream) -> TokenStream {
         _ => return TokenStream::new(),
     };
 
+    let marker_trait_body = if kind.is_state() {
+        quote! {
+            fn requires_empty_state_key(&self) -> bool {
+                match self {
+                    #(
+                        #variant_arms(content) => {
+                            #ruma_events::StateEventContent::requires_empty_state_key(content)
+                        },
+                    )*
+                    Self::_Custom(content) => {
+                        #ruma_events::StateEventContent::requires_empty_state_key(content)
+                    },
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
     let ident = kind.to_content_enum();
     quote! {
         #[automatically_derived]
-        impl #ruma_events::#marker_trait for #ident {}
+        impl #ruma_events::#marker_trait for #ident {
+            #marker_trait_body
+        }
     }
 }
 
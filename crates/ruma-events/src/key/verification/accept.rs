@@ -180,8 +180,8 @@ mod tests {
     #[cfg(feature = "unstable-pre-spec")]
     use super::AcceptEventContent;
     use super::{
-        AcceptMethod, AcceptToDeviceEventContent, HashAlgorithm, KeyAgreementProtocol,
-        MessageAuthenticationCode, SasV1Content, ShortAuthenticationString, _CustomContent,
+        _CustomContent, AcceptMethod, AcceptToDeviceEventContent, HashAlgorithm,
+        KeyAgreementProtocol, MessageAuthenticationCode, SasV1Content, ShortAuthenticationString,
     };
     #[cfg(feature = "unstable-pre-spec")]
     use crate::key::verification::Relation;
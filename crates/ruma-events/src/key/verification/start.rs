@@ -260,9 +260,9 @@ mod tests {
     };
 
     use super::{
-        HashAlgorithm, KeyAgreementProtocol, MessageAuthenticationCode, SasV1Content,
-        SasV1ContentInit, ShortAuthenticationString, StartMethod, StartToDeviceEventContent,
-        _CustomContent,
+        _CustomContent, HashAlgorithm, KeyAgreementProtocol, MessageAuthenticationCode,
+        SasV1Content, SasV1ContentInit, ShortAuthenticationString, StartMethod,
+        StartToDeviceEventContent,
     };
     #[cfg(feature = "unstable-pre-spec")]
     use super::{ReciprocateV1Content, StartEventContent};
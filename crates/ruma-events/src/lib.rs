@@ -115,11 +115,11 @@
 #![warn(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
 
 use js_int::Int;
 use ruma_identifiers::{EventEncryptionAlgorithm, RoomVersionId};
-use ruma_serde::Raw;
+use ruma_serde::{CanonicalJsonError, Raw};
 use serde::{
     de::{self, IgnoredAny},
     Deserialize, Serialize,
@@ -131,6 +131,8 @@ use self::room::redaction::SyncRedactionEvent;
 mod enums;
 mod error;
 mod event_kinds;
+#[cfg(test)]
+mod test_utils;
 
 // Hack to allow both ruma-events itself and external crates (or tests) to use procedural macros
 // that expect `ruma_events` to exist in the prelude.
@@ -274,6 +276,17 @@ pub trait EventContent: Sized + Serialize {
 
     /// Constructs the given event content.
     fn from_parts(event_type: &str, content: &RawJsonValue) -> serde_json::Result<Self>;
+
+    /// Serializes the event content as [canonical JSON].
+    ///
+    /// Object keys are sorted and there is no insignificant whitespace. Numbers must be integers in
+    /// the range of `js_int::Int`, otherwise `CanonicalJsonError::IntConvert` is returned.
+    ///
+    /// [canonical JSON]: https://matrix.org/docs/spec/appendices#canonical-json
+    fn to_canonical_json(&self) -> Result<String, CanonicalJsonError> {
+        let value = ruma_serde::to_canonical_value(self)?;
+        ruma_serde::to_canonical_json_string(&value)
+    }
 }
 
 /// Trait to define the behavior of redacting an event.
@@ -330,7 +343,16 @@ pub trait ToDeviceEventContent: EventContent {}
 pub trait MessageEventContent: EventContent {}
 
 /// Marker trait for the content of a state event.
-pub trait StateEventContent: EventContent {}
+pub trait StateEventContent: EventContent {
+    /// Whether events with this content must have an empty state key.
+    ///
+    /// State events with such content fail to serialize and deserialize if their state key is not
+    /// empty. The derive macro implements this for content marked with
+    /// `#[ruma_event(empty_state_key)]`.
+    fn requires_empty_state_key(&self) -> bool {
+        false
+    }
+}
 
 /// The base trait that all redacted event content types implement.
 ///
@@ -423,3 +445,26 @@ where
 {
     serde_json::from_str(val.get()).map_err(E::custom)
 }
+
+/// Helper function for the `EventContent` derive that rejects fields of `content` that are not in
+/// `known_fields`, if the `unstable-strict-content` feature is activated.
+///
+/// This lives here rather than in the generated code so that the feature of ruma-events applies,
+/// not one of the crate that uses the derive. It is a separate pass over the content, so with the
+/// feature activated, the content JSON is parsed twice: once here to collect its keys, and once
+/// more to deserialize the content type.
+#[doc(hidden)]
+pub fn deny_unknown_content_fields(
+    content: &RawJsonValue,
+    known_fields: &'static [&'static str],
+) -> serde_json::Result<()> {
+    if !cfg!(feature = "unstable-strict-content") {
+        return Ok(());
+    }
+
+    let fields: BTreeMap<String, IgnoredAny> = serde_json::from_str(content.get())?;
+    match fields.keys().find(|f| !known_fields.contains(&f.as_str())) {
+        Some(field) => Err(de::Error::unknown_field(field, known_fields)),
+        None => Ok(()),
+    }
+}
@@ -1,17 +1,24 @@
 //! Types for the *m.room.server_acl* event.
 
+use std::{collections::BTreeSet, str::FromStr};
+
 use ruma_events_macros::EventContent;
+use ruma_identifiers::ServerName;
 use serde::{Deserialize, Serialize};
 
-use crate::StateEvent;
+use crate::{
+    AnyPossiblyRedactedStateEvent, AnyPossiblyRedactedSyncStateEvent, AnyRedactedStateEvent,
+    AnyRedactedSyncStateEvent, AnyStateEvent, AnySyncStateEvent, InvalidInput, StateEvent,
+};
 
 /// An event to indicate which servers are permitted to participate in the room.
 pub type ServerAclEvent = StateEvent<ServerAclEventContent>;
 
 /// The payload for `ServerAclEvent`.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.server_acl", kind = State)]
+#[ruma_event(type = "m.room.server_acl", kind = State, empty_state_key)]
 pub struct ServerAclEventContent {
     /// True to allow server names that are IP address literals. False to deny.
     ///
@@ -19,6 +26,7 @@ pub struct ServerAclEventContent {
     /// names are strongly discouraged in order to require legitimate homeservers to be
     /// backed by a valid registered domain name.
     #[serde(default = "ruma_serde::default_true", skip_serializing_if = "ruma_serde::is_true")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "default_true_schema"))]
     pub allow_ip_literals: bool,
 
     /// The server names to allow in the room, excluding any port information. Wildcards may
@@ -27,6 +35,13 @@ pub struct ServerAclEventContent {
     ///
     /// **This defaults to an empty list when not provided, effectively disallowing every
     /// server.**
+    ///
+    /// If you activate the `compat` feature, entries that are not strings are skipped during
+    /// deserialization instead of failing.
+    #[cfg_attr(
+        feature = "compat",
+        serde(deserialize_with = "ruma_serde::ignore_invalid_vec_items")
+    )]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub allow: Vec<String>,
 
@@ -35,6 +50,13 @@ pub struct ServerAclEventContent {
     /// matches exactly one character.
     ///
     /// This defaults to an empty list when not provided.
+    ///
+    /// If you activate the `compat` feature, entries that are not strings are skipped during
+    /// deserialization instead of failing.
+    #[cfg_attr(
+        feature = "compat",
+        serde(deserialize_with = "ruma_serde::ignore_invalid_vec_items")
+    )]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub deny: Vec<String>,
 }
@@ -45,15 +67,599 @@ impl ServerAclEventContent {
     pub fn new(allow_ip_literals: bool, allow: Vec<String>, deny: Vec<String>) -> Self {
         Self { allow_ip_literals, allow, deny }
     }
+
+    /// Creates a new `ServerAclEventContent` like [`new`](Self::new), but makes sure that every
+    /// entry of `allow` and `deny` is a valid server name pattern.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidInput` will be returned if any pattern is invalid, see [`validate`](Self::validate).
+    pub fn new_checked(
+        allow_ip_literals: bool,
+        allow: Vec<String>,
+        deny: Vec<String>,
+    ) -> Result<Self, InvalidInput> {
+        let content = Self::new(allow_ip_literals, allow, deny);
+        content.validate()?;
+        Ok(content)
+    }
+
+    /// Creates a new `ServerAclEventContent` that allows every server, including IP literals.
+    pub fn allow_all() -> Self {
+        Self::new(true, vec!["*".to_owned()], Vec::new())
+    }
+
+    /// Creates a new `ServerAclEventContent` that denies every server.
+    ///
+    /// Since the `allow` list is empty, no server matches it, so there is no need to add anything
+    /// to `deny`.
+    pub fn deny_all() -> Self {
+        Self::new(false, Vec::new(), Vec::new())
+    }
+
+    /// Adds the server name patterns of the given iterator to the list of allowed servers.
+    pub fn with_allowed<I>(mut self, servers: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.allow.extend(servers.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sorts `allow` and `deny` and removes duplicate entries from them.
+    ///
+    /// This makes the serialized form of equivalent ACLs identical, which is useful for stable
+    /// diffs. Which servers are allowed doesn't depend on the order of the entries, but
+    /// [`explain`](Self::explain) may report a different matching pattern afterwards.
+    pub fn sorted(mut self) -> Self {
+        for list in &mut [&mut self.allow, &mut self.deny] {
+            list.sort_unstable();
+            list.dedup();
+        }
+
+        self
+    }
+
+    /// Checks that every entry of `allow` and `deny` is a valid server name pattern.
+    ///
+    /// Invalid patterns can never match any server, so they are most likely mistakes. A pattern
+    /// is considered invalid if:
+    ///
+    /// * it is empty,
+    /// * it contains a port, i.e. a `:` that is not part of a bracketed IPv6 address,
+    /// * it contains any character other than ASCII letters, digits, `-`, `.`, and the wildcards
+    ///   `*` and `?`, apart from the brackets, colons and dots of an IPv6 address enclosed in `[`
+    ///   and `]`.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidInput` will be returned for the first invalid pattern.
+    pub fn validate(&self) -> Result<(), InvalidInput> {
+        self.allow.iter().chain(&self.deny).try_for_each(|pattern| validate_pattern(pattern))
+    }
+
+    /// Parses ACL rules from a compact, whitespace-separated textual format.
+    ///
+    /// This is meant for command-line tools and configuration files. Every rule is one of:
+    ///
+    /// * `allow:<pattern>`, which adds `<pattern>` to `allow`,
+    /// * `deny:<pattern>`, which adds `<pattern>` to `deny`,
+    /// * `ip`, which sets `allow_ip_literals` to `true`,
+    /// * `!ip`, which sets `allow_ip_literals` to `false`.
+    ///
+    /// For example, `allow:*.example.com deny:evil.com !ip` allows every subdomain of
+    /// `example.com` except for `evil.com` and denies IP literals. The order of the patterns is
+    /// kept. Like in the event content, `allow_ip_literals` is `true` if neither `ip` nor `!ip`
+    /// is given. If both are given, the last one wins. This is also available through the
+    /// `FromStr` implementation.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidInput` will be returned for the first rule that is not one of the above, and for
+    /// the first invalid pattern, see [`validate`](Self::validate).
+    pub fn from_rules_str(rules: &str) -> Result<Self, InvalidInput> {
+        let mut content = Self::new(true, Vec::new(), Vec::new());
+
+        for rule in rules.split_whitespace() {
+            match rule {
+                "ip" => content.allow_ip_literals = true,
+                "!ip" => content.allow_ip_literals = false,
+                _ => {
+                    if let Some(pattern) = rule.strip_prefix("allow:") {
+                        content.allow.push(pattern.to_owned());
+                    } else if let Some(pattern) = rule.strip_prefix("deny:") {
+                        content.deny.push(pattern.to_owned());
+                    } else {
+                        return Err(InvalidInput(format!(
+                            "`{}` is not a valid server ACL rule, expected `allow:<pattern>`, \
+                             `deny:<pattern>`, `ip` or `!ip`",
+                            rule
+                        )));
+                    }
+                }
+            }
+        }
+
+        content.validate()?;
+        Ok(content)
+    }
+
+    /// Creates a new `ServerAclEventContentBuilder`.
+    pub fn builder() -> ServerAclEventContentBuilder {
+        ServerAclEventContentBuilder::new()
+    }
+
+    /// Returns true if and only if the server is allowed by the ACL rules.
+    ///
+    /// The port of the server name is ignored, and the host is matched against the entries of
+    /// `allow` and `deny` ignoring ASCII case differences. IP literals are only allowed if
+    /// `allow_ip_literals` is true, and a server that matches any entry of `deny` is denied even if
+    /// it also matches an entry of `allow`.
+    pub fn is_allowed(&self, server_name: &ServerName) -> bool {
+        self.explain(server_name).is_allowed()
+    }
+
+    /// Checks the server against the ACL rules like [`is_allowed`](Self::is_allowed), but returns
+    /// the reason for the decision.
+    ///
+    /// This is useful to find out which pattern is responsible for a server being denied. If
+    /// several patterns of a list match, the first one is returned.
+    pub fn explain(&self, server_name: &ServerName) -> AclDecision<'_> {
+        if !self.allow_ip_literals && server_name.is_ip_literal() {
+            return AclDecision::IpLiteralDenied;
+        }
+
+        if let Some(pattern) = find_matching_pattern(server_name, &self.deny) {
+            return AclDecision::Denied(pattern);
+        }
+
+        match find_matching_pattern(server_name, &self.allow) {
+            Some(pattern) => AclDecision::Allowed(pattern),
+            None => AclDecision::NotAllowed,
+        }
+    }
+
+    /// Checks whether the given host matches any of the given ACL patterns.
+    ///
+    /// This is how both `allow` and `deny` are checked by [`is_allowed`](Self::is_allowed): a port
+    /// in `host` is ignored, wildcards are expanded and ASCII case differences are ignored. A
+    /// `host` that is not a valid server name doesn't match anything.
+    pub fn matches_any(host: &str, patterns: &[String]) -> bool {
+        ServerName::parse(host)
+            .map_or(false, |server_name| find_matching_pattern(server_name, patterns).is_some())
+    }
+
+    /// Splits the given servers into those that are allowed and those that are denied by the ACL
+    /// rules, see [`is_allowed`](Self::is_allowed).
+    ///
+    /// This is useful to preview which servers would be affected by an ACL change. The order of
+    /// the servers is kept in both lists.
+    pub fn partition<'a>(
+        &self,
+        servers: impl IntoIterator<Item = &'a ServerName>,
+    ) -> (Vec<&'a ServerName>, Vec<&'a ServerName>) {
+        servers.into_iter().partition(|server_name| self.is_allowed(server_name))
+    }
+
+    /// Returns true if every server denied by `other` is also denied by this ACL, i.e. if this
+    /// ACL is at least as restrictive as `other`.
+    ///
+    /// Deciding this exactly would require comparing arbitrary glob patterns, so this only
+    /// recognizes the simple cases and returns `false` if it can't tell. It returns `true` if this
+    /// ACL doesn't allow any server, or if all of the following hold:
+    ///
+    /// * this ACL doesn't allow IP literals, or `other` allows them too,
+    /// * every entry of `other.deny` is covered by an entry of this ACL's `deny`,
+    /// * every entry of this ACL's `allow` is covered by an entry of `other.allow`.
+    ///
+    /// A pattern covers another one if they are equal ignoring ASCII case, if it is `*`, if it
+    /// matches the other one as a host name when that one has no wildcards, or if it consists of
+    /// `*` followed by a suffix without wildcards that the other one ends with, like
+    /// `*.example.com` covers `*.matrix.example.com`. Notably, this returns `false` if this ACL
+    /// allows IP literals and `other` doesn't, even if none of the entries of this ACL's `allow`
+    /// can match an IP literal, and for patterns like `a*` and `ab*` that only differ after a
+    /// wildcard.
+    pub fn covers(&self, other: &Self) -> bool {
+        if self.allow.is_empty() {
+            return true;
+        }
+
+        (!self.allow_ip_literals || other.allow_ip_literals)
+            && other.deny.iter().all(|pattern| is_covered_by_any(pattern, &self.deny))
+            && self.allow.iter().all(|pattern| is_covered_by_any(pattern, &other.allow))
+    }
+
+    /// Returns advisories about likely mistakes in this ACL, in the order of the checks below.
+    ///
+    /// These don't make the ACL invalid, so they are meant to be shown to users editing it
+    /// rather than rejecting it. The following are reported:
+    ///
+    /// * the first entry of `deny` that only consists of `*`, which denies every server,
+    /// * an empty `allow` list, which also denies every server,
+    /// * an entry of `deny` that is an IP literal while `allow_ip_literals` is true, one warning
+    ///   for each such entry.
+    ///
+    /// An empty list of warnings means that none of these were found.
+    pub fn warnings(&self) -> Vec<AclWarning<'_>> {
+        let mut warnings = Vec::new();
+
+        if let Some(pattern) = self
+            .deny
+            .iter()
+            .find(|pattern| !pattern.is_empty() && pattern.bytes().all(|b| b == b'*'))
+        {
+            warnings.push(AclWarning::DenyAll(pattern));
+        }
+
+        if self.allow.is_empty() {
+            warnings.push(AclWarning::EmptyAllow);
+        }
+
+        if self.allow_ip_literals {
+            warnings.extend(
+                self.deny
+                    .iter()
+                    .filter(|pattern| {
+                        ServerName::parse(pattern).map_or(false, |server_name| {
+                            server_name.port().is_none() && server_name.is_ip_literal()
+                        })
+                    })
+                    .map(|pattern| AclWarning::DeniedIpLiteral(pattern)),
+            );
+        }
+
+        warnings
+    }
+
+    /// Compares this ACL to the `previous` one, returning the entries that were added to and
+    /// removed from `allow` and `deny`, and whether `allow_ip_literals` changed.
+    ///
+    /// This is useful for audit logs of ACL changes. The order of the entries is kept, but an
+    /// entry is listed only once even if it appears multiple times in a list.
+    pub fn diff<'a>(&'a self, previous: &'a Self) -> AclDiff<'a> {
+        AclDiff {
+            allow_ip_literals: if self.allow_ip_literals == previous.allow_ip_literals {
+                None
+            } else {
+                Some(self.allow_ip_literals)
+            },
+            added_allow: missing_entries(&self.allow, &previous.allow),
+            removed_allow: missing_entries(&previous.allow, &self.allow),
+            added_deny: missing_entries(&self.deny, &previous.deny),
+            removed_deny: missing_entries(&previous.deny, &self.deny),
+        }
+    }
+}
+
+impl AnyPossiblyRedactedStateEvent {
+    /// If this is an `m.room.server_acl` event, returns the ACL rules that it puts into effect.
+    ///
+    /// For an un-redacted event, this is its content. For a redacted event, it is the minimal
+    /// content the spec mandates: redaction removes every key of the content, so the defaults
+    /// apply and IP literals are allowed, but since `allow` is empty, every server is denied.
+    ///
+    /// Returns `None` for events of any other type.
+    pub fn effective_server_acl(&self) -> Option<ServerAclEventContent> {
+        match self {
+            Self::Regular(AnyStateEvent::RoomServerAcl(ev)) => Some(ev.content.clone()),
+            Self::Redacted(AnyRedactedStateEvent::RoomServerAcl(_)) => Some(redacted_content()),
+            _ => None,
+        }
+    }
+}
+
+impl AnyPossiblyRedactedSyncStateEvent {
+    /// If this is an `m.room.server_acl` event, returns the ACL rules that it puts into effect.
+    ///
+    /// See [`AnyPossiblyRedactedStateEvent::effective_server_acl`] for details.
+    pub fn effective_server_acl(&self) -> Option<ServerAclEventContent> {
+        match self {
+            Self::Regular(AnySyncStateEvent::RoomServerAcl(ev)) => Some(ev.content.clone()),
+            Self::Redacted(AnyRedactedSyncStateEvent::RoomServerAcl(_)) => Some(redacted_content()),
+            _ => None,
+        }
+    }
+}
+
+/// The content of a redacted `m.room.server_acl` event with the defaults of all keys.
+fn redacted_content() -> ServerAclEventContent {
+    ServerAclEventContent::new(true, Vec::new(), Vec::new())
+}
+
+impl FromStr for ServerAclEventContent {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_rules_str(s)
+    }
+}
+
+/// The reason for a server being allowed or denied by a `ServerAclEventContent`.
+///
+/// This is returned by [`ServerAclEventContent::explain`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum AclDecision<'a> {
+    /// The server is allowed because it matches the given entry of `allow`, and no entry of
+    /// `deny`.
+    Allowed(&'a str),
+
+    /// The server is denied because it matches the given entry of `deny`.
+    Denied(&'a str),
+
+    /// The server is denied because it doesn't match any entry of `allow`.
+    NotAllowed,
+
+    /// The server is denied because it is an IP literal and `allow_ip_literals` is false.
+    IpLiteralDenied,
+}
+
+impl AclDecision<'_> {
+    /// Returns true if and only if the server is allowed.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed(_))
+    }
+}
+
+/// A likely mistake in a `ServerAclEventContent`.
+///
+/// This is returned by [`ServerAclEventContent::warnings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum AclWarning<'a> {
+    /// The given entry of `deny` matches every server, so no server is allowed.
+    DenyAll(&'a str),
+
+    /// `allow` is empty, so no server is allowed.
+    EmptyAllow,
+
+    /// The given entry of `deny` is an IP literal, but `allow_ip_literals` is true.
+    ///
+    /// Entries are matched against the host of a server name as a string, so the same address
+    /// written differently, like `[::ffff:1.2.3.4]` instead of `1.2.3.4`, is not denied. Setting
+    /// `allow_ip_literals` to false denies every IP literal reliably.
+    DeniedIpLiteral(&'a str),
+}
+
+/// The changes between two `ServerAclEventContent`s.
+///
+/// This is returned by [`ServerAclEventContent::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct AclDiff<'a> {
+    /// The new value of `allow_ip_literals`, if it changed.
+    pub allow_ip_literals: Option<bool>,
+
+    /// The entries that were added to `allow`.
+    pub added_allow: Vec<&'a str>,
+
+    /// The entries that were removed from `allow`.
+    pub removed_allow: Vec<&'a str>,
+
+    /// The entries that were added to `deny`.
+    pub added_deny: Vec<&'a str>,
+
+    /// The entries that were removed from `deny`.
+    pub removed_deny: Vec<&'a str>,
+}
+
+impl AclDiff<'_> {
+    /// Returns true if and only if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.allow_ip_literals.is_none()
+            && self.added_allow.is_empty()
+            && self.removed_allow.is_empty()
+            && self.added_deny.is_empty()
+            && self.removed_deny.is_empty()
+    }
+}
+
+/// The JSON schema for a boolean that defaults to `true`.
+///
+/// schemars leaves out the default of fields whose default value isn't serialized, which is the
+/// case for `allow_ip_literals`.
+#[cfg(feature = "schemars")]
+fn default_true_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    let mut schema = gen.subschema_for::<bool>().into_object();
+    schema.metadata().default = Some(true.into());
+    schema.into()
+}
+
+/// Returns the entries of `list` that are not in `other`, without duplicates.
+fn missing_entries<'a>(list: &'a [String], other: &[String]) -> Vec<&'a str> {
+    let other: BTreeSet<&str> = other.iter().map(String::as_str).collect();
+    let mut seen = BTreeSet::new();
+
+    list.iter()
+        .map(String::as_str)
+        .filter(|entry| !other.contains(entry) && seen.insert(*entry))
+        .collect()
+}
+
+/// Returns the first of the given ACL `patterns` that matches the host of `server_name`.
+fn find_matching_pattern<'a>(server_name: &ServerName, patterns: &'a [String]) -> Option<&'a str> {
+    patterns.iter().map(String::as_str).find(|pattern| matches_pattern(server_name, pattern))
+}
+
+/// Checks whether every host matched by `pattern` is also matched by one of `patterns`.
+///
+/// This can return `false` even if that's the case, see [`ServerAclEventContent::covers`].
+fn is_covered_by_any(pattern: &str, patterns: &[String]) -> bool {
+    patterns.iter().any(|general| pattern_covers(general, pattern))
+}
+
+/// Checks whether every host matched by the ACL pattern `specific` is also matched by the ACL
+/// pattern `general`, for the simple cases.
+fn pattern_covers(general: &str, specific: &str) -> bool {
+    let has_wildcards = |pattern: &str| pattern.bytes().any(|b| matches!(b, b'*' | b'?'));
+
+    if general == "*" || general.eq_ignore_ascii_case(specific) {
+        return true;
+    }
+
+    if !has_wildcards(specific) {
+        return match ServerName::parse(specific) {
+            Ok(server_name) if server_name.port().is_none() => {
+                matches_pattern(server_name, general)
+            }
+            _ => false,
+        };
+    }
+
+    match general.strip_prefix('*') {
+        Some(suffix) if !has_wildcards(suffix) => {
+            let (specific, suffix) = (specific.as_bytes(), suffix.as_bytes());
+            specific.len() >= suffix.len()
+                && specific[specific.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether the host of `server_name` matches the ACL `pattern`.
+///
+/// Like DNS names, hosts and patterns are compared ignoring ASCII case differences. Most patterns
+/// don't contain any wildcards, so those are compared directly instead of running the glob
+/// matcher.
+fn matches_pattern(server_name: &ServerName, pattern: &str) -> bool {
+    if pattern.bytes().any(|b| matches!(b, b'*' | b'?')) {
+        server_name.matches_glob_ignore_ascii_case(pattern)
+    } else {
+        server_name.host().eq_ignore_ascii_case(pattern)
+    }
+}
+
+fn validate_pattern(pattern: &str) -> Result<(), InvalidInput> {
+    // Colons are only allowed inside of a bracketed IPv6 address.
+    let (ipv6_address, rest) = match pattern.strip_prefix('[') {
+        Some(p) => match p.find(']') {
+            Some(idx) => (Some(&p[..idx]), &p[idx + 1..]),
+            None => (None, pattern),
+        },
+        None => (None, pattern),
+    };
+
+    if rest.contains(':') {
+        return Err(InvalidInput(format!(
+            "server ACL pattern `{}` must not contain a port",
+            pattern
+        )));
+    }
+
+    let is_valid = match ipv6_address {
+        Some(address) => {
+            !address.is_empty()
+                && rest.is_empty()
+                && address
+                    .bytes()
+                    .all(|b| b.is_ascii_hexdigit() || matches!(b, b':' | b'.' | b'*' | b'?'))
+        }
+        None => {
+            !pattern.is_empty()
+                && pattern
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'*' | b'?'))
+        }
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(InvalidInput(format!("`{}` is not a valid server ACL pattern", pattern)))
+    }
+}
+
+/// A builder for `ServerAclEventContent`.
+///
+/// IP literals are denied and the `allow` and `deny` lists are empty unless configured otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct ServerAclEventContentBuilder {
+    allow_ip_literals: bool,
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl ServerAclEventContentBuilder {
+    /// Creates a new `ServerAclEventContentBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether server names that are IP address literals are allowed.
+    pub fn allow_ip_literals(mut self, allow_ip_literals: bool) -> Self {
+        self.allow_ip_literals = allow_ip_literals;
+        self
+    }
+
+    /// Adds a server name pattern to the list of allowed servers.
+    pub fn allow(mut self, server: impl Into<String>) -> Self {
+        self.allow.push(server.into());
+        self
+    }
+
+    /// Adds a server name pattern to the list of denied servers.
+    pub fn deny(mut self, server: impl Into<String>) -> Self {
+        self.deny.push(server.into());
+        self
+    }
+
+    /// Creates the `ServerAclEventContent`.
+    pub fn build(self) -> ServerAclEventContent {
+        ServerAclEventContent::new(self.allow_ip_literals, self.allow, self.deny)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+
+    use js_int::uint;
+    use matches::assert_matches;
+    use ruma_common::MilliSecondsSinceUnixEpoch;
+    use ruma_identifiers::{event_id, room_id, user_id, ServerName};
     use ruma_serde::Raw;
-    use serde_json::{from_value as from_json_value, json};
+    use serde_json::{
+        from_value as from_json_value, json, to_value as to_json_value,
+        value::to_raw_value as to_raw_json_value,
+    };
 
-    use super::ServerAclEventContent;
-    use crate::StateEvent;
+    use super::{
+        matches_pattern, AclDecision, AclDiff, AclWarning, ServerAclEventContent,
+        ServerAclEventContentBuilder,
+    };
+    use crate::{
+        test_utils::assert_content_roundtrip, AnyPossiblyRedactedStateEvent,
+        AnyPossiblyRedactedSyncStateEvent, AnyStateEvent, EventContent, InvalidInput, StateEvent,
+        StrippedStateEvent, Unsigned,
+    };
+
+    #[test]
+    fn raw_allow_ip_literals() {
+        let json_data = json!({
+            "content": {
+                "allow": ["*"],
+                "allow_ip_literals": false,
+                "deny": ["evil.com"]
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.server_acl"
+        });
+        let raw = from_json_value::<Raw<StateEvent<ServerAclEventContent>>>(json_data).unwrap();
+
+        assert_eq!(
+            raw.get_field_path::<bool>(&["content", "allow_ip_literals"]).unwrap(),
+            Some(false)
+        );
+        assert!(raw.get_field_path::<&str>(&["content", "deny", "0"]).is_err());
+        assert_eq!(raw.get_field_path::<bool>(&["unsigned", "allow_ip_literals"]).unwrap(), None);
+        assert!(raw.get_field_path::<bool>(&["content", "allow"]).is_err());
+        assert!(raw.get_field_path::<bool>(&["state_key", "allow_ip_literals"]).is_err());
+    }
 
     #[test]
     fn default_values() {
@@ -76,4 +682,702 @@ mod tests {
         assert!(server_acl_event.content.allow.is_empty());
         assert!(server_acl_event.content.deny.is_empty());
     }
+
+    #[test]
+    fn roundtrip_default_values() {
+        let content =
+            assert_content_roundtrip::<ServerAclEventContent>("m.room.server_acl", json!({}));
+
+        assert!(content.allow_ip_literals);
+        assert!(content.allow.is_empty());
+        assert!(content.deny.is_empty());
+    }
+
+    #[test]
+    fn roundtrip_all_fields() {
+        let content = assert_content_roundtrip::<ServerAclEventContent>(
+            "m.room.server_acl",
+            json!({
+                "allow_ip_literals": false,
+                "allow": ["*", "ruma.io"],
+                "deny": ["*.evil.com", "evil.matrix.org"],
+            }),
+        );
+
+        assert!(!content.allow_ip_literals);
+        assert_eq!(content.allow, vec!["*".to_owned(), "ruma.io".to_owned()]);
+        assert_eq!(content.deny, vec!["*.evil.com".to_owned(), "evil.matrix.org".to_owned()]);
+    }
+
+    #[test]
+    fn non_string_entries() {
+        let json_data = json!({
+            "allow": ["*", 42, null],
+            "deny": [1, "evil.matrix.org"],
+        });
+
+        #[cfg(feature = "compat")]
+        {
+            let content = from_json_value::<ServerAclEventContent>(json_data).unwrap();
+            assert_eq!(content.allow, vec!["*".to_owned()]);
+            assert_eq!(content.deny, vec!["evil.matrix.org".to_owned()]);
+        }
+
+        #[cfg(not(feature = "compat"))]
+        assert!(from_json_value::<ServerAclEventContent>(json_data).is_err());
+    }
+
+    #[test]
+    fn canonical_json() {
+        let content = ServerAclEventContent::new(
+            false,
+            vec!["*".to_owned(), "ruma.io".to_owned()],
+            vec!["*.evil.com".to_owned()],
+        );
+
+        assert_eq!(
+            content.to_canonical_json().unwrap(),
+            r#"{"allow":["*","ruma.io"],"allow_ip_literals":false,"deny":["*.evil.com"]}"#
+        );
+    }
+
+    #[test]
+    fn unknown_field() {
+        let content = to_raw_json_value(&json!({ "allow": ["*"], "alow": ["ruma.io"] })).unwrap();
+        let result = ServerAclEventContent::from_parts("m.room.server_acl", &content);
+
+        #[cfg(not(feature = "unstable-strict-content"))]
+        assert_eq!(result.unwrap().allow, vec!["*".to_owned()]);
+
+        #[cfg(feature = "unstable-strict-content")]
+        assert!(result.unwrap_err().to_string().contains("unknown field `alow`"));
+    }
+
+    #[test]
+    fn non_empty_state_key_rejected() {
+        let json_data = json!({
+            "content": { "allow": ["*"] },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "@carl:example.com",
+            "type": "m.room.server_acl"
+        });
+
+        assert!(from_json_value::<StateEvent<ServerAclEventContent>>(json_data.clone()).is_err());
+        assert!(from_json_value::<AnyStateEvent>(json_data.clone()).is_err());
+        assert!(from_json_value::<StrippedStateEvent<ServerAclEventContent>>(json_data).is_err());
+    }
+
+    #[test]
+    fn non_empty_state_key_not_serialized() {
+        let mut event = StateEvent {
+            content: ServerAclEventContent::allow_all(),
+            event_id: event_id!("$h29iv0s8:example.com"),
+            origin_server_ts: MilliSecondsSinceUnixEpoch(uint!(1)),
+            prev_content: None,
+            room_id: room_id!("!n8f893n9:example.com"),
+            sender: user_id!("@carl:example.com"),
+            state_key: "".into(),
+            unsigned: Unsigned::default(),
+        };
+        assert_eq!(to_json_value(&event).unwrap()["state_key"], "");
+
+        event.state_key = "@carl:example.com".into();
+        assert!(to_json_value(&event).is_err());
+    }
+
+    #[test]
+    fn event_type() {
+        let content = ServerAclEventContent::new(false, Vec::new(), Vec::new());
+        assert_eq!(content.event_type(), "m.room.server_acl");
+    }
+
+    #[test]
+    fn acl_ignores_port() {
+        let acl_event = ServerAclEventContent {
+            allow_ip_literals: true,
+            allow: vec!["*".to_owned()],
+            deny: vec!["1.1.1.1".to_owned()],
+        };
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("1.1.1.1:8000").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("1.1.1.2:8000").unwrap()));
+    }
+
+    #[test]
+    fn acl_allow_ip_literal() {
+        let acl_event = ServerAclEventContent {
+            allow_ip_literals: true,
+            allow: vec!["*".to_owned()],
+            deny: Vec::new(),
+        };
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("127.0.0.1").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("[::1]:8448").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("matrix.org").unwrap()));
+    }
+
+    #[test]
+    fn acl_deny_ip_literal() {
+        let acl_event = ServerAclEventContent {
+            allow_ip_literals: false,
+            allow: vec!["*".to_owned()],
+            deny: Vec::new(),
+        };
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("127.0.0.1").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("[::1]:8448").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("matrix.org").unwrap()));
+    }
+
+    #[test]
+    fn acl_wildcard_deny_overrides_allow() {
+        let acl_event = ServerAclEventContent {
+            allow_ip_literals: true,
+            allow: vec!["good.matrix.org".to_owned(), "*.ruma.io".to_owned()],
+            deny: vec!["*.matrix.org".to_owned(), "evil.ruma.io".to_owned()],
+        };
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("good.matrix.org").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("evil.ruma.io").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("good.ruma.io").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("ruma.io").unwrap()));
+    }
+
+    #[test]
+    fn acl_question_mark_wildcard() {
+        let acl_event = ServerAclEventContent {
+            allow_ip_literals: true,
+            allow: vec!["matrix.?rg".to_owned()],
+            deny: Vec::new(),
+        };
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("matrix.org").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("matrix.erg").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("matrix.rg").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("matrix.oorg").unwrap()));
+    }
+
+    #[test]
+    fn acl_ignores_ascii_case() {
+        let acl_event = ServerAclEventContent {
+            allow_ip_literals: true,
+            allow: vec!["*.Example.COM".to_owned(), "Matrix.org".to_owned()],
+            deny: vec!["EVIL.example.com".to_owned()],
+        };
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("host.example.com").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("Host.EXAMPLE.com:8448").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("MATRIX.ORG").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("evil.Example.com").unwrap()));
+    }
+
+    #[test]
+    fn matches_any() {
+        assert!(!ServerAclEventContent::matches_any("matrix.org", &[]));
+
+        let patterns = ["*".to_owned()];
+        assert!(ServerAclEventContent::matches_any("matrix.org", &patterns));
+        assert!(ServerAclEventContent::matches_any("127.0.0.1:8448", &patterns));
+        assert!(!ServerAclEventContent::matches_any("not a host", &patterns));
+
+        let patterns = ["ruma.io".to_owned(), "matrix.org".to_owned(), "[::1]".to_owned()];
+        assert!(ServerAclEventContent::matches_any("matrix.org", &patterns));
+        assert!(ServerAclEventContent::matches_any("RUMA.io:8448", &patterns));
+        assert!(ServerAclEventContent::matches_any("[::1]", &patterns));
+        assert!(!ServerAclEventContent::matches_any("sub.matrix.org", &patterns));
+        assert!(!ServerAclEventContent::matches_any("ruma.io.evil.com", &patterns));
+    }
+
+    #[test]
+    fn acl_empty_allow_denies_everything() {
+        let acl_event =
+            ServerAclEventContent { allow_ip_literals: true, allow: Vec::new(), deny: Vec::new() };
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("matrix.org").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("127.0.0.1").unwrap()));
+    }
+
+    #[test]
+    fn fast_path_agrees_with_glob() {
+        let patterns = [
+            "matrix.org",
+            "Matrix.org",
+            "*.matrix.org",
+            "matrix.?rg",
+            "ruma.io",
+            "*",
+            "127.0.0.1",
+            "127.0.0.*",
+            "[::1]",
+            "[::?]",
+        ];
+        let servers = [
+            "matrix.org",
+            "MATRIX.org",
+            "matrix.org:8448",
+            "good.matrix.org",
+            "ruma.io",
+            "ruma.io.evil.com",
+            "127.0.0.1",
+            "127.0.0.1:8000",
+            "127.0.0.2",
+            "[::1]:8448",
+        ];
+
+        for pattern in &patterns {
+            for server in &servers {
+                let server_name = <&ServerName>::try_from(*server).unwrap();
+                assert_eq!(
+                    matches_pattern(server_name, pattern),
+                    server_name.matches_glob_ignore_ascii_case(pattern),
+                    "pattern `{}`, server `{}`",
+                    pattern,
+                    server
+                );
+            }
+        }
+
+        let acl_event = ServerAclEventContent {
+            allow_ip_literals: true,
+            allow: vec!["ruma.io".to_owned(), "*.matrix.org".to_owned(), "127.0.0.*".to_owned()],
+            deny: vec!["evil.matrix.org".to_owned(), "127.0.0.?".to_owned()],
+        };
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("ruma.io:8448").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("RUMA.io").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("good.matrix.org").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("evil.matrix.org").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("127.0.0.1").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("127.0.0.10").unwrap()));
+    }
+
+    #[test]
+    fn allow_all() {
+        let acl_event = ServerAclEventContent::allow_all();
+
+        assert!(acl_event.allow_ip_literals);
+        assert_eq!(acl_event.allow, vec!["*".to_owned()]);
+        assert!(acl_event.deny.is_empty());
+
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("matrix.org").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("ruma.io:8448").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("127.0.0.1").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("[::1]:8448").unwrap()));
+    }
+
+    #[test]
+    fn deny_all() {
+        let acl_event = ServerAclEventContent::deny_all();
+
+        assert!(!acl_event.allow_ip_literals);
+        assert!(acl_event.allow.is_empty());
+        assert!(acl_event.deny.is_empty());
+
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("matrix.org").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("ruma.io:8448").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("127.0.0.1").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("[::1]:8448").unwrap()));
+    }
+
+    #[test]
+    fn with_allowed() {
+        let servers = ["matrix.org", "*.ruma.io"];
+        let acl_event = ServerAclEventContent::deny_all().with_allowed(servers.iter().copied());
+
+        assert_eq!(acl_event.allow, vec!["matrix.org".to_owned(), "*.ruma.io".to_owned()]);
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("matrix.org").unwrap()));
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("good.ruma.io").unwrap()));
+        assert!(!acl_event.is_allowed(<&ServerName>::try_from("evil.com").unwrap()));
+
+        let acl_event = acl_event.with_allowed(vec!["evil.com".to_owned()]);
+        assert_eq!(acl_event.allow.len(), 3);
+        assert!(acl_event.is_allowed(<&ServerName>::try_from("evil.com").unwrap()));
+    }
+
+    #[test]
+    fn sorted() {
+        let acl_event = ServerAclEventContent::new(
+            false,
+            vec!["ruma.io".to_owned(), "*.matrix.org".to_owned(), "ruma.io".to_owned()],
+            vec!["spam.org".to_owned(), "evil.com".to_owned(), "evil.com".to_owned()],
+        )
+        .sorted();
+
+        assert_eq!(
+            to_json_value(&acl_event).unwrap(),
+            json!({
+                "allow_ip_literals": false,
+                "allow": ["*.matrix.org", "ruma.io"],
+                "deny": ["evil.com", "spam.org"],
+            })
+        );
+    }
+
+    #[test]
+    fn partition() {
+        let acl_event = ServerAclEventContent::new(
+            false,
+            vec!["*".to_owned()],
+            vec!["*.evil.com".to_owned(), "bad.ruma.io".to_owned()],
+        );
+        let servers: Vec<&ServerName> =
+            ["matrix.org", "spam.evil.com", "ruma.io:8448", "127.0.0.1", "bad.ruma.io", "evil.com"]
+                .iter()
+                .map(|s| <&ServerName>::try_from(*s).unwrap())
+                .collect();
+
+        let (allowed, denied) = acl_event.partition(servers.iter().copied());
+        assert_eq!(allowed, ["matrix.org", "ruma.io:8448", "evil.com"]);
+        assert_eq!(denied, ["spam.evil.com", "127.0.0.1", "bad.ruma.io"]);
+    }
+
+    #[test]
+    fn explain() {
+        let acl_event = ServerAclEventContent::new(
+            false,
+            vec!["ruma.io".to_owned(), "*".to_owned()],
+            vec!["*.evil.com".to_owned()],
+        );
+
+        assert_eq!(
+            acl_event.explain(<&ServerName>::try_from("spam.evil.com").unwrap()),
+            AclDecision::Denied("*.evil.com")
+        );
+        assert_eq!(
+            acl_event.explain(<&ServerName>::try_from("ruma.io:8448").unwrap()),
+            AclDecision::Allowed("ruma.io")
+        );
+        assert_eq!(
+            acl_event.explain(<&ServerName>::try_from("matrix.org").unwrap()),
+            AclDecision::Allowed("*")
+        );
+        assert_eq!(
+            acl_event.explain(<&ServerName>::try_from("[::1]").unwrap()),
+            AclDecision::IpLiteralDenied
+        );
+        assert!(!AclDecision::IpLiteralDenied.is_allowed());
+
+        let acl_event = ServerAclEventContent::new(true, vec!["ruma.io".to_owned()], Vec::new());
+        assert_eq!(
+            acl_event.explain(<&ServerName>::try_from("matrix.org").unwrap()),
+            AclDecision::NotAllowed
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let previous = ServerAclEventContent::new(
+            true,
+            vec!["*".to_owned(), "ruma.io".to_owned()],
+            vec!["evil.com".to_owned()],
+        );
+        let current = ServerAclEventContent::new(
+            false,
+            vec!["*".to_owned(), "matrix.org".to_owned(), "matrix.org".to_owned()],
+            vec!["evil.com".to_owned(), "*.evil.com".to_owned(), "spam.org".to_owned()],
+        );
+
+        assert_eq!(
+            current.diff(&previous),
+            AclDiff {
+                allow_ip_literals: Some(false),
+                added_allow: vec!["matrix.org"],
+                removed_allow: vec!["ruma.io"],
+                added_deny: vec!["*.evil.com", "spam.org"],
+                removed_deny: Vec::new(),
+            }
+        );
+
+        let diff = previous.diff(&current);
+        assert_eq!(diff.allow_ip_literals, Some(true));
+        assert_eq!(diff.added_allow, ["ruma.io"]);
+        assert_eq!(diff.removed_allow, ["matrix.org"]);
+        assert!(diff.added_deny.is_empty());
+        assert_eq!(diff.removed_deny, ["*.evil.com", "spam.org"]);
+        assert!(!diff.is_empty());
+
+        assert!(current.diff(&current.clone()).is_empty());
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn json_schema() {
+        let schema = to_json_value(schemars::schema_for!(ServerAclEventContent)).unwrap();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["allow_ip_literals"]["type"], "boolean");
+        assert_eq!(properties["allow_ip_literals"]["default"], true);
+        assert_eq!(properties["allow"]["type"], "array");
+        assert_eq!(properties["allow"]["items"], json!({ "type": "string" }));
+        assert_eq!(properties["deny"]["type"], "array");
+        assert_eq!(properties["deny"]["items"], json!({ "type": "string" }));
+        // All fields have defaults, so none of them is required.
+        assert!(schema.get("required").is_none());
+    }
+
+    #[test]
+    fn effective_server_acl() {
+        let mut json_data = json!({
+            "content": {
+                "allow_ip_literals": false,
+                "allow": ["*"],
+                "deny": ["evil.com"]
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.server_acl"
+        });
+
+        let event = from_json_value::<AnyPossiblyRedactedStateEvent>(json_data.clone()).unwrap();
+        assert_matches!(event, AnyPossiblyRedactedStateEvent::Regular(_));
+        let content = event.effective_server_acl().unwrap();
+        assert!(!content.allow_ip_literals);
+        assert_eq!(content.allow, vec!["*"]);
+        assert_eq!(content.deny, vec!["evil.com"]);
+
+        json_data["content"] = json!({});
+        json_data["unsigned"] = json!({
+            "redacted_because": {
+                "content": { "reason": "redacted because" },
+                "event_id": "$h29iv0s9:example.com",
+                "origin_server_ts": 2,
+                "redacts": "$h29iv0s8:example.com",
+                "sender": "@carl:example.com",
+                "type": "m.room.redaction"
+            }
+        });
+
+        let event = from_json_value::<AnyPossiblyRedactedStateEvent>(json_data.clone()).unwrap();
+        assert_matches!(event, AnyPossiblyRedactedStateEvent::Redacted(_));
+        let content = event.effective_server_acl().unwrap();
+        assert!(content.allow_ip_literals);
+        assert!(content.allow.is_empty());
+        assert!(content.deny.is_empty());
+        assert_eq!(
+            content.explain(<&ServerName>::try_from("matrix.org").unwrap()),
+            AclDecision::NotAllowed
+        );
+
+        json_data.as_object_mut().unwrap().remove("room_id");
+        let event = from_json_value::<AnyPossiblyRedactedSyncStateEvent>(json_data).unwrap();
+        assert_matches!(event, AnyPossiblyRedactedSyncStateEvent::Redacted(_));
+        assert!(event.effective_server_acl().unwrap().allow.is_empty());
+
+        let event = from_json_value::<AnyPossiblyRedactedStateEvent>(json!({
+            "content": { "name": "The room" },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.name"
+        }))
+        .unwrap();
+        assert!(event.effective_server_acl().is_none());
+    }
+
+    #[test]
+    fn builder_defaults() {
+        let acl_event = ServerAclEventContentBuilder::new().build();
+
+        assert!(!acl_event.allow_ip_literals);
+        assert!(acl_event.allow.is_empty());
+        assert!(acl_event.deny.is_empty());
+    }
+
+    #[test]
+    fn builder() {
+        let acl_event = ServerAclEventContent::builder()
+            .allow_ip_literals(true)
+            .allow("*.matrix.org")
+            .allow("ruma.io".to_owned())
+            .deny("evil.matrix.org")
+            .deny("*.evil.ruma.io")
+            .build();
+        let expected = ServerAclEventContent::new(
+            true,
+            vec!["*.matrix.org".to_owned(), "ruma.io".to_owned()],
+            vec!["evil.matrix.org".to_owned(), "*.evil.ruma.io".to_owned()],
+        );
+
+        assert_eq!(acl_event.allow_ip_literals, expected.allow_ip_literals);
+        assert_eq!(acl_event.allow, expected.allow);
+        assert_eq!(acl_event.deny, expected.deny);
+    }
+
+    #[test]
+    fn new_checked_valid_patterns() {
+        assert!(ServerAclEventContent::new_checked(
+            false,
+            vec!["*".to_owned(), "*.matrix.org".to_owned(), "matrix.?rg".to_owned()],
+            vec!["evil-server.ruma.io".to_owned(), "10.0.0.*".to_owned(), "[::1]".to_owned()],
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn new_checked_pattern_with_port() {
+        assert_eq!(
+            ServerAclEventContent::new_checked(
+                true,
+                vec!["matrix.org:8448".to_owned()],
+                Vec::new()
+            )
+            .unwrap_err(),
+            InvalidInput("server ACL pattern `matrix.org:8448` must not contain a port".into())
+        );
+        assert!(ServerAclEventContent::new_checked(
+            true,
+            Vec::new(),
+            vec!["[::1]:8448".to_owned()]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn covers() {
+        let existing = ServerAclEventContent::new(
+            true,
+            vec!["*".to_owned()],
+            vec!["evil.example.com".to_owned(), "*.bad.org".to_owned()],
+        );
+        let proposed = ServerAclEventContent::new(
+            false,
+            vec!["*.example.com".to_owned(), "matrix.org".to_owned()],
+            vec!["*.example.com".to_owned(), "*.org".to_owned(), "[::1]".to_owned()],
+        );
+
+        assert!(proposed.covers(&existing));
+        assert!(!existing.covers(&proposed));
+        assert!(existing.covers(&existing));
+        assert!(proposed.covers(&proposed));
+        assert!(ServerAclEventContent::deny_all().covers(&ServerAclEventContent::allow_all()));
+        assert!(!ServerAclEventContent::allow_all().covers(&ServerAclEventContent::deny_all()));
+
+        let existing = ServerAclEventContent::new(
+            false,
+            vec!["*.EXAMPLE.com".to_owned()],
+            vec!["e?il.example.com".to_owned()],
+        );
+        let proposed = ServerAclEventContent::new(
+            false,
+            vec!["matrix.example.com".to_owned(), "*.matrix.example.com".to_owned()],
+            vec!["*il.example.com".to_owned()],
+        );
+        assert!(proposed.covers(&existing));
+    }
+
+    #[test]
+    fn covers_conservative() {
+        // None of the allowed servers can be an IP literal, but this isn't detected.
+        let existing = ServerAclEventContent::new(false, vec!["*".to_owned()], Vec::new());
+        let proposed =
+            ServerAclEventContent::new(true, vec!["*.example.com".to_owned()], Vec::new());
+        assert!(!proposed.covers(&existing));
+
+        // `a*` matches everything `ab*` matches, but only `*` followed by a suffix is understood.
+        let existing =
+            ServerAclEventContent::new(true, vec!["*".to_owned()], vec!["ab*".to_owned()]);
+        let proposed =
+            ServerAclEventContent::new(true, vec!["*".to_owned()], vec!["a*".to_owned()]);
+        assert!(!proposed.covers(&existing));
+    }
+
+    #[test]
+    fn warnings() {
+        let content = ServerAclEventContent::new(
+            true,
+            vec!["*".to_owned()],
+            vec!["evil.com".to_owned(), "**".to_owned(), "*".to_owned()],
+        );
+        assert_eq!(content.warnings(), vec![AclWarning::DenyAll("**")]);
+
+        let content = ServerAclEventContent::new(true, Vec::new(), vec!["evil.com".to_owned()]);
+        assert_eq!(content.warnings(), vec![AclWarning::EmptyAllow]);
+
+        let content = ServerAclEventContent::new(
+            true,
+            vec!["*".to_owned(), "10.0.0.1".to_owned()],
+            vec!["1.2.3.4".to_owned(), "1.2.3.*".to_owned(), "[::1]".to_owned()],
+        );
+        assert_eq!(
+            content.warnings(),
+            vec![AclWarning::DeniedIpLiteral("1.2.3.4"), AclWarning::DeniedIpLiteral("[::1]")]
+        );
+
+        let content = ServerAclEventContent::new(true, Vec::new(), vec!["*".to_owned()]);
+        assert_eq!(content.warnings(), vec![AclWarning::DenyAll("*"), AclWarning::EmptyAllow]);
+    }
+
+    #[test]
+    fn no_warnings() {
+        let content = ServerAclEventContent::new(
+            false,
+            vec!["*".to_owned()],
+            vec!["evil.com".to_owned(), "*.evil.com".to_owned(), "1.2.3.4".to_owned()],
+        );
+        assert_eq!(content.warnings(), Vec::new());
+        assert_eq!(ServerAclEventContent::allow_all().warnings(), Vec::new());
+    }
+
+    #[test]
+    fn from_rules_str() {
+        let content =
+            ServerAclEventContent::from_rules_str("allow:*.example.com deny:evil.com  !ip\n")
+                .unwrap();
+        assert!(!content.allow_ip_literals);
+        assert_eq!(content.allow, vec!["*.example.com".to_owned()]);
+        assert_eq!(content.deny, vec!["evil.com".to_owned()]);
+
+        let content: ServerAclEventContent =
+            "deny:evil.com allow:* allow:[::1] !ip ip deny:*.evil.com".parse().unwrap();
+        assert!(content.allow_ip_literals);
+        assert_eq!(content.allow, vec!["*".to_owned(), "[::1]".to_owned()]);
+        assert_eq!(content.deny, vec!["evil.com".to_owned(), "*.evil.com".to_owned()]);
+
+        let content = ServerAclEventContent::from_rules_str("").unwrap();
+        assert!(content.allow_ip_literals);
+        assert!(content.allow.is_empty());
+        assert!(content.deny.is_empty());
+    }
+
+    #[test]
+    fn from_rules_str_invalid() {
+        assert_eq!(
+            ServerAclEventContent::from_rules_str("allow:* block:evil.com").unwrap_err(),
+            InvalidInput(
+                "`block:evil.com` is not a valid server ACL rule, expected `allow:<pattern>`, \
+                 `deny:<pattern>`, `ip` or `!ip`"
+                    .into()
+            )
+        );
+        assert_eq!(
+            ServerAclEventContent::from_rules_str("allow:").unwrap_err(),
+            InvalidInput("`` is not a valid server ACL pattern".into())
+        );
+        assert!(ServerAclEventContent::from_rules_str("deny:evil.com:8448").is_err());
+        assert!("allow:* IP".parse::<ServerAclEventContent>().is_err());
+    }
+
+    #[test]
+    fn new_checked_empty_pattern() {
+        assert_eq!(
+            ServerAclEventContent::new_checked(true, vec![String::new()], Vec::new()).unwrap_err(),
+            InvalidInput("`` is not a valid server ACL pattern".into())
+        );
+    }
+
+    #[test]
+    fn new_checked_invalid_characters() {
+        assert!(ServerAclEventContent::new_checked(
+            true,
+            vec!["matrix .org".to_owned()],
+            Vec::new()
+        )
+        .is_err());
+        assert!(
+            ServerAclEventContent::new_checked(true, Vec::new(), vec!["[::1".to_owned()]).is_err()
+        );
+        assert!(
+            ServerAclEventContent::new_checked(true, Vec::new(), vec!["[]".to_owned()]).is_err()
+        );
+    }
 }
@@ -0,0 +1,21 @@
+//! Helpers for tests of event content types.
+
+use ruma_serde::Raw;
+use serde_json::{to_value as to_json_value, value::to_raw_value, Value as JsonValue};
+
+use crate::{EventContent, RawExt};
+
+/// Assert that `json` deserializes to `T` via [`Raw`] and serializes back to the same JSON.
+///
+/// JSON objects are compared without regard to key order, so this catches fields that are dropped
+/// or defaults that are serialized when they shouldn't be. Returns the deserialized content for
+/// further assertions.
+pub(crate) fn assert_content_roundtrip<T: EventContent>(event_type: &str, json: JsonValue) -> T {
+    let raw = Raw::<T>::from_json(to_raw_value(&json).unwrap());
+    let content = raw.deserialize_content(event_type).unwrap();
+
+    assert_eq!(content.event_type(), event_type);
+    assert_eq!(to_json_value(&content).unwrap(), json);
+
+    content
+}
@@ -3,8 +3,8 @@ use maplit::btreemap;
 use matches::assert_matches;
 use ruma_common::MilliSecondsSinceUnixEpoch;
 use ruma_events::{
-    custom::CustomEventContent, AnyStateEvent, AnySyncRoomEvent, AnySyncStateEvent, MessageEvent,
-    StateEvent, Unsigned,
+    custom::CustomEventContent, AnyStateEvent, AnyStateEventContent, AnySyncRoomEvent,
+    AnySyncStateEvent, EventContent, MessageEvent, StateEvent, Unsigned,
 };
 use ruma_identifiers::{event_id, room_id, user_id};
 use serde_json::{
@@ -148,3 +148,42 @@ fn deserialize_custom_message_sync_event() {
         Ok(AnySyncRoomEvent::Message(_))
     );
 }
+
+#[test]
+fn deserialize_unknown_state_event_into_custom_content() {
+    let json_data = json!({
+        "content": {
+            "custom": 10,
+            "nested": { "key": "value" }
+        },
+        "event_id": "$h29iv0s8:example.com",
+        "origin_server_ts": 10,
+        "room_id": "!room:room.com",
+        "sender": "@carl:example.com",
+        "state_key": "",
+        "type": "io.ruma.unknown",
+    });
+
+    // Known and unknown event types go through the same `EventContent` interface.
+    let event = from_json_value::<AnyStateEvent>(json_data.clone()).unwrap();
+    assert_eq!(event.event_type(), "io.ruma.unknown");
+    assert_eq!(event.content().event_type(), "io.ruma.unknown");
+
+    let content = match event.content() {
+        AnyStateEventContent::_Custom(content) => content,
+        content => panic!("unexpected content: {:?}", content),
+    };
+    assert_eq!(content.event_type, "io.ruma.unknown");
+    assert_eq!(
+        content.data,
+        btreemap! {
+            "custom".into() => 10.into(),
+            "nested".into() => json!({ "key": "value" }),
+        }
+    );
+
+    // The generic holder can also be used directly.
+    let event = from_json_value::<StateEvent<CustomEventContent>>(json_data).unwrap();
+    assert_eq!(event.content.event_type, "io.ruma.unknown");
+    assert_eq!(event.content.data["custom"], 10);
+}
@@ -8,12 +8,13 @@ use ruma_events::{
         create::RedactedCreateEventContent,
         message::RedactedMessageEventContent,
         redaction::{RedactionEventContent, SyncRedactionEvent},
+        server_acl::{RedactedServerAclEventContent, ServerAclEventContent},
     },
     AnyMessageEvent, AnyMessageEventContent, AnyRedactedMessageEvent,
     AnyRedactedMessageEventContent, AnyRedactedStateEventContent, AnyRedactedSyncMessageEvent,
     AnyRedactedSyncStateEvent, AnyRoomEvent, AnyStateEventContent, AnySyncRoomEvent, EventContent,
-    Redact, RedactContent, RedactedMessageEvent, RedactedSyncMessageEvent, RedactedSyncStateEvent,
-    RedactedUnsigned, Unsigned,
+    Redact, RedactContent, RedactedEventContent, RedactedMessageEvent, RedactedSyncMessageEvent,
+    RedactedSyncStateEvent, RedactedUnsigned, Unsigned,
 };
 use ruma_identifiers::{event_id, room_id, user_id, RoomVersionId};
 use ruma_serde::Raw;
@@ -361,3 +362,28 @@ fn redact_state_content() {
         }) if creator == user_id!("@carl:example.com")
     );
 }
+
+#[test]
+fn redact_server_acl_content() {
+    let content = ServerAclEventContent::new(
+        false,
+        vec!["*.example.org".to_owned()],
+        vec!["evil.example.org".to_owned()],
+    );
+
+    // None of the keys of `m.room.server_acl` are kept by the redaction algorithm, in any room
+    // version.
+    for version in &[
+        RoomVersionId::Version1,
+        RoomVersionId::Version2,
+        RoomVersionId::Version3,
+        RoomVersionId::Version4,
+        RoomVersionId::Version5,
+        RoomVersionId::Version6,
+    ] {
+        let redacted = content.clone().redact(version);
+        assert_matches!(redacted, RedactedServerAclEventContent { .. });
+        assert_eq!(redacted.event_type(), "m.room.server_acl");
+        assert!(!redacted.has_serialize_fields());
+    }
+}
@@ -1,9 +1,8 @@
-use js_int::uint;
 use ruma_events::{
     room::{join_rules::JoinRule, topic::TopicEventContent},
     AnyStateEventContent, AnyStrippedStateEvent, StrippedStateEvent,
 };
-use ruma_identifiers::{mxc_uri, user_id};
+use ruma_identifiers::user_id;
 use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
 
 #[test]
@@ -111,23 +110,62 @@ fn deserialize_stripped_state_events() {
         _ => unreachable!(),
     }
 
+    // The top-level `thumbnail_info` and `thumbnail_url` are unknown fields, which are only
+    // ignored without `unstable-strict-content`.
+    #[cfg(feature = "unstable-strict-content")]
+    assert!(from_json_value::<AnyStrippedStateEvent>(avatar_event).is_err());
+
+    #[cfg(not(feature = "unstable-strict-content"))]
+    {
+        use js_int::uint;
+        use ruma_identifiers::mxc_uri;
+
+        let event = from_json_value::<AnyStrippedStateEvent>(avatar_event).unwrap();
+        match event {
+            AnyStrippedStateEvent::RoomAvatar(event) => {
+                let image_info = event.content.info.unwrap();
+                let expected_url = mxc_uri!("mxc://example.com/iMag3");
+
+                #[cfg(feature = "unstable-pre-spec")]
+                let expected_url = Some(expected_url);
+
+                assert_eq!(image_info.height.unwrap(), uint!(128));
+                assert_eq!(image_info.width.unwrap(), uint!(128));
+                assert_eq!(image_info.mimetype.unwrap(), "image/jpeg");
+                assert_eq!(image_info.size.unwrap(), uint!(1024));
+                assert_eq!(image_info.thumbnail_info.unwrap().size.unwrap(), uint!(32));
+                assert_eq!(event.content.url, expected_url);
+                assert_eq!(event.state_key, "");
+                assert_eq!(event.sender.to_string(), "@example:localhost");
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "unstable-strict-content")]
+#[test]
+fn deserialize_stripped_state_event_strict() {
+    let avatar_event = json!({
+        "type": "m.room.avatar",
+        "state_key": "",
+        "sender": "@example:localhost",
+        "content": {
+            "info": {
+                "h": 128,
+                "w": 128,
+                "mimetype": "image/jpeg",
+                "size": 1024
+            },
+            "url": "mxc://example.com/iMag3"
+        }
+    });
+
     let event = from_json_value::<AnyStrippedStateEvent>(avatar_event).unwrap();
     match event {
         AnyStrippedStateEvent::RoomAvatar(event) => {
-            let image_info = event.content.info.unwrap();
-            let expected_url = mxc_uri!("mxc://example.com/iMag3");
-
-            #[cfg(feature = "unstable-pre-spec")]
-            let expected_url = Some(expected_url);
-
-            assert_eq!(image_info.height.unwrap(), uint!(128));
-            assert_eq!(image_info.width.unwrap(), uint!(128));
-            assert_eq!(image_info.mimetype.unwrap(), "image/jpeg");
-            assert_eq!(image_info.size.unwrap(), uint!(1024));
-            assert_eq!(image_info.thumbnail_info.unwrap().size.unwrap(), uint!(32));
-            assert_eq!(event.content.url, expected_url);
+            assert_eq!(event.content.info.unwrap().mimetype.as_deref(), Some("image/jpeg"));
             assert_eq!(event.state_key, "");
-            assert_eq!(event.sender.to_string(), "@example:localhost");
         }
         _ => unreachable!(),
     }
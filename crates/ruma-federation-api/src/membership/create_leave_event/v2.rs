@@ -31,7 +31,6 @@ ruma_api! {
         pub pdu: Raw<Pdu>,
     }
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -42,13 +41,6 @@ impl<'a> Request<'a> {
     }
 }
 
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
 #[cfg(all(test, feature = "server"))]
 mod tests {
     use ruma_api::OutgoingResponse;
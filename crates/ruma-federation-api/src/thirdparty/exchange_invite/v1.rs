@@ -33,7 +33,6 @@ ruma_api! {
         pub content: &'a ThirdPartyInvite,
     }
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -48,10 +47,3 @@ impl<'a> Request<'a> {
         Self { room_id, kind: EventType::RoomMember, sender, state_key, content }
     }
 }
-
-impl Response {
-    /// Creates a new `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -0,0 +1,40 @@
+// To pass args to criterion, use this form
+// `cargo bench --bench server_name -- --save-baseline <name>`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ruma_identifiers_validation::server_name::validate;
+
+const SERVER_NAMES: &[&str] = &[
+    "matrix.org",
+    "ruma.io:8448",
+    "matrix-client.matrix.org",
+    "a.b-c.d0.1e.example.com.",
+    "127.0.0.1:8448",
+    "[::1]",
+    "[1234:5678::abcd]:8448",
+    "[::ffff:1.2.3.4]",
+    "example-.com",
+    "example.com:hello",
+];
+
+fn validate_server_names(c: &mut Criterion) {
+    c.bench_function("validate server names", |b| {
+        b.iter(|| {
+            for server_name in SERVER_NAMES {
+                let _ = validate(black_box(server_name));
+            }
+        })
+    });
+}
+
+fn validate_long_server_name(c: &mut Criterion) {
+    let label = "a".repeat(63);
+    let server_name = format!("{0}.{0}.{0}.{1}:8448", label, "a".repeat(57));
+    assert_eq!(server_name.len(), 254);
+
+    c.bench_function("validate long server name", |b| b.iter(|| validate(black_box(&server_name))));
+}
+
+criterion_group!(benches, validate_server_names, validate_long_server_name);
+
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 //! Error conditions.
 
-use std::fmt;
+use core::fmt;
 
 /// An error encountered when trying to parse an invalid ID string.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
@@ -54,4 +54,61 @@ impl fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
+
+/// An error encountered when trying to parse an invalid server name.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ServerNameError {
+    /// The server name is empty.
+    Empty,
+
+    /// The server name was given as bytes that are not valid UTF-8.
+    InvalidUtf8,
+
+    /// The host is not a valid DNS name.
+    ///
+    /// A valid DNS name consists of labels separated by `.`, with an optional trailing `.`. Every
+    /// label is between 1 and 63 bytes long, consists of ASCII letters, digits and `-`, and
+    /// doesn't start or end with `-`. The whole name is at most 253 bytes long, not counting
+    /// the trailing `.`.
+    InvalidHost,
+
+    /// The host starts with `[` but isn't a valid IPv6 address enclosed in brackets.
+    ///
+    /// This includes IPv6 addresses with a zone ID, like `[fe80::1%eth0]`.
+    InvalidIpv6,
+
+    /// The host is followed by something other than `:` and a valid port number.
+    InvalidPort,
+
+    /// The server name exceeds 255 bytes.
+    MaximumLengthExceeded,
+}
+
+impl fmt::Display for ServerNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ServerNameError::Empty => "server name is empty",
+            ServerNameError::InvalidUtf8 => "server name is not valid UTF-8",
+            ServerNameError::InvalidHost => "server name host is not a valid DNS name",
+            ServerNameError::InvalidIpv6 => "server name host is not a valid IPv6 address",
+            ServerNameError::InvalidPort => "server name port is invalid",
+            ServerNameError::MaximumLengthExceeded => "server name exceeds 255 bytes",
+        };
+
+        write!(f, "{}", message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ServerNameError {}
+
+impl From<ServerNameError> for Error {
+    fn from(err: ServerNameError) -> Self {
+        match err {
+            ServerNameError::MaximumLengthExceeded => Error::MaximumLengthExceeded,
+            _ => Error::InvalidServerName,
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Parsing of IP address literals that doesn't depend on `std::net`.
+//!
+//! This accepts exactly the same strings as the `FromStr` implementations of `std::net::Ipv4Addr`
+//! and `std::net::Ipv6Addr`.
+
+/// Checks whether the given string is an IPv4 address in dotted decimal notation.
+pub fn is_ipv4_address(s: &str) -> bool {
+    let mut parser = Parser::new(s);
+    parser.read_ipv4_addr() && parser.is_eof()
+}
+
+/// Checks whether the given string is an IPv6 address, without enclosing brackets.
+pub fn is_ipv6_address(s: &str) -> bool {
+    let mut parser = Parser::new(s);
+    parser.read_ipv6_addr() && parser.is_eof()
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input: input.as_bytes() }
+    }
+
+    fn is_eof(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    /// Runs the given parser, resetting the input to its previous state if it fails.
+    fn read_atomically(&mut self, f: impl FnOnce(&mut Self) -> bool) -> bool {
+        let input = self.input;
+        let success = f(self);
+        if !success {
+            self.input = input;
+        }
+
+        success
+    }
+
+    fn read_given_char(&mut self, c: u8) -> bool {
+        match self.input.split_first() {
+            Some((&first, rest)) if first == c => {
+                self.input = rest;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Reads a number with the given radix and at most `max_digits` digits that has to fit into
+    /// `max_value`.
+    ///
+    /// Decimal numbers with more than one digit must not start with a zero.
+    fn read_number(&mut self, radix: u32, max_digits: usize, max_value: u32) -> bool {
+        self.read_atomically(|p| {
+            let mut value = 0_u32;
+            let mut digit_count = 0;
+
+            while digit_count < max_digits {
+                let digit = match p.input.first().and_then(|&b| (b as char).to_digit(radix)) {
+                    Some(digit) => digit,
+                    None => break,
+                };
+
+                if radix == 10 && digit_count == 1 && value == 0 {
+                    return false;
+                }
+
+                value = value * radix + digit;
+                if value > max_value {
+                    return false;
+                }
+
+                p.input = &p.input[1..];
+                digit_count += 1;
+            }
+
+            digit_count > 0
+        })
+    }
+
+    fn read_ipv4_addr(&mut self) -> bool {
+        self.read_atomically(|p| {
+            (0..4).all(|i| (i == 0 || p.read_given_char(b'.')) && p.read_number(10, 3, 255))
+        })
+    }
+
+    /// Reads up to `limit` groups of an IPv6 address.
+    ///
+    /// Returns the number of groups that were read, and whether the last two of them were an
+    /// embedded IPv4 address.
+    fn read_ipv6_groups(&mut self, limit: usize) -> (usize, bool) {
+        for i in 0..limit {
+            // An embedded IPv4 address takes up the space of two groups.
+            if i < limit.saturating_sub(1)
+                && self
+                    .read_atomically(|p| (i == 0 || p.read_given_char(b':')) && p.read_ipv4_addr())
+            {
+                return (i + 2, true);
+            }
+
+            if !self.read_atomically(|p| {
+                (i == 0 || p.read_given_char(b':')) && p.read_number(16, 4, 0xFFFF)
+            }) {
+                return (i, false);
+            }
+        }
+
+        (limit, false)
+    }
+
+    fn read_ipv6_addr(&mut self) -> bool {
+        self.read_atomically(|p| {
+            let (head_size, head_ipv4) = p.read_ipv6_groups(8);
+            if head_size == 8 {
+                return true;
+            }
+
+            // An embedded IPv4 address must be at the end.
+            if head_ipv4 {
+                return false;
+            }
+
+            if !(p.read_given_char(b':') && p.read_given_char(b':')) {
+                return false;
+            }
+
+            // `::` stands for at least one group of zeros.
+            p.read_ipv6_groups(8 - (head_size + 1));
+            true
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::{is_ipv4_address, is_ipv6_address};
+
+    const INPUTS: &[&str] = &[
+        "",
+        ".",
+        "0.0.0.0",
+        "1.2.3.4",
+        "127.0.0.1",
+        "255.255.255.255",
+        "256.0.0.1",
+        "1.2.3",
+        "1.2.3.4.5",
+        "1.2.3.4.",
+        ".1.2.3.4",
+        "01.2.3.4",
+        "1.2.3.04",
+        "1.2.3.0",
+        "1..2.3",
+        "1.2.3.1000",
+        "1.2.3.a",
+        "+1.2.3.4",
+        "::",
+        "::1",
+        "1::",
+        "1::1",
+        ":1",
+        "1:",
+        ":::",
+        "1:::1",
+        "1::2::3",
+        "1:2:3:4:5:6:7:8",
+        "1:2:3:4:5:6:7:8:9",
+        "1:2:3:4:5:6:7",
+        "1:2:3:4:5:6:7::",
+        "::2:3:4:5:6:7:8",
+        "1:2:3:4::5:6:7:8",
+        "1234:5678::abcd",
+        "12345::",
+        "0000:0000::0000",
+        "00000::",
+        "abcd:EF01::",
+        "g::",
+        "bad::",
+        "::ffff:1.2.3.4",
+        "::1.2.3.4",
+        "1:2:3:4:5:6:1.2.3.4",
+        "1:2:3:4:5:6:7:1.2.3.4",
+        "1:2:3:4:5:1.2.3.4",
+        "::1.2.3.4:1",
+        "1.2.3.4::",
+        "::01.2.3.4",
+        "::256.2.3.4",
+        "::ffff:1.2.3",
+        "[::1]",
+        " ::1",
+    ];
+
+    #[test]
+    fn ipv4_same_as_std() {
+        for input in INPUTS {
+            assert_eq!(is_ipv4_address(input), input.parse::<Ipv4Addr>().is_ok(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn ipv6_same_as_std() {
+        for input in INPUTS {
+            assert_eq!(is_ipv6_address(input), input.parse::<Ipv6Addr>().is_ok(), "{}", input);
+        }
+    }
+}
@@ -1,9 +1,11 @@
 #![doc(html_favicon_url = "https://www.ruma.io/favicon.ico")]
 #![doc(html_logo_url = "https://www.ruma.io/images/logo.png")]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 pub mod device_key_id;
 pub mod error;
 pub mod event_id;
+mod ip;
 pub mod key_id;
 pub mod mxc_uri;
 pub mod room_alias_id;
@@ -13,9 +15,9 @@ pub mod room_version_id;
 pub mod server_name;
 pub mod user_id;
 
-use std::num::NonZeroU8;
+use core::num::NonZeroU8;
 
-pub use error::Error;
+pub use error::{Error, ServerNameError};
 
 /// All identifiers must be 255 bytes or less.
 const MAX_BYTES: usize = 255;
@@ -1,31 +1,42 @@
-use crate::error::Error;
-
-pub fn validate(server_name: &str) -> Result<(), Error> {
-    use std::net::Ipv6Addr;
+use crate::{
+    error::ServerNameError,
+    ip::{is_ipv4_address, is_ipv6_address},
+    MAX_BYTES,
+};
 
+/// Checks whether the given string is a valid server name, returning the reason if it isn't.
+///
+/// A valid server name is a DNS name, an IPv4 address or an IPv6 address enclosed in brackets,
+/// optionally followed by `:` and a port.
+///
+/// IPv6 addresses with a zone ID, like `[fe80::1%eth0]`, are rejected with
+/// `ServerNameError::InvalidIpv6`, with or without a port. The Matrix specification doesn't allow
+/// them, and a zone ID only has a meaning on the host that chose it.
+pub fn validate(server_name: &str) -> Result<(), ServerNameError> {
     if server_name.is_empty() {
-        return Err(Error::InvalidServerName);
+        return Err(ServerNameError::Empty);
+    }
+
+    if server_name.len() > MAX_BYTES {
+        return Err(ServerNameError::MaximumLengthExceeded);
     }
 
-    let end_of_host = if server_name.starts_with('[') {
+    let end_of_host = if is_ipv6_literal(server_name) {
         let end_of_ipv6 = match server_name.find(']') {
             Some(idx) => idx,
-            None => return Err(Error::InvalidServerName),
+            None => return Err(ServerNameError::InvalidIpv6),
         };
 
-        if server_name[1..end_of_ipv6].parse::<Ipv6Addr>().is_err() {
-            return Err(Error::InvalidServerName);
+        if !is_ipv6_address(&server_name[1..end_of_ipv6]) {
+            return Err(ServerNameError::InvalidIpv6);
         }
 
         end_of_ipv6 + 1
     } else {
         let end_of_host = server_name.find(':').unwrap_or_else(|| server_name.len());
 
-        if server_name[..end_of_host]
-            .bytes()
-            .any(|byte| !(byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.'))
-        {
-            return Err(Error::InvalidServerName);
+        if !is_valid_dns_name(&server_name[..end_of_host]) {
+            return Err(ServerNameError::InvalidHost);
         }
 
         end_of_host
@@ -36,11 +47,136 @@ pub fn validate(server_name: &str) -> Result<(), Error> {
             // hostname is followed by something other than ":port"
             server_name.as_bytes()[end_of_host] != b':'
             // the remaining characters after ':' are not a valid port
-            || server_name[end_of_host + 1..].parse::<u16>().is_err()
+            || !is_valid_port(&server_name[end_of_host + 1..])
         )
     {
-        Err(Error::InvalidServerName)
+        Err(ServerNameError::InvalidPort)
     } else {
         Ok(())
     }
 }
+
+/// Checks whether the given host part of a valid server name is an IP address literal.
+///
+/// IPv6 literals are required to be enclosed in brackets, everything else that parses as an IPv4
+/// address is an IPv4 literal.
+pub fn is_ip_literal(host: &str) -> bool {
+    is_ipv6_literal(host) || is_ipv4_address(host)
+}
+
+/// Checks whether the given string is a valid port.
+///
+/// A valid port is a decimal number between 1 and 65535 without a sign or leading zeros.
+fn is_valid_port(port: &str) -> bool {
+    !port.starts_with('0')
+        && port.bytes().all(|b| b.is_ascii_digit())
+        && port.parse::<u16>().is_ok()
+}
+
+/// Checks whether the given string is a valid DNS name.
+///
+/// The name must not be longer than 253 bytes, not counting the trailing dot of a fully-qualified
+/// name, and all of its labels must be between 1 and 63 bytes long, consist of ASCII letters,
+/// digits and `-`, and not start or end with `-`.
+///
+/// This checks the whole name in a single pass over its bytes.
+fn is_valid_dns_name(name: &str) -> bool {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.len() > 253 {
+        return false;
+    }
+
+    let mut label_len = 0;
+    let mut prev = b'.';
+    for &b in name.as_bytes() {
+        if b == b'.' {
+            if label_len == 0 || prev == b'-' {
+                return false;
+            }
+
+            label_len = 0;
+        } else if b.is_ascii_alphanumeric() || (b == b'-' && label_len != 0) {
+            label_len += 1;
+            if label_len > 63 {
+                return false;
+            }
+        } else {
+            return false;
+        }
+
+        prev = b;
+    }
+
+    label_len != 0 && prev != b'-'
+}
+
+fn is_ipv6_literal(server_name: &str) -> bool {
+    server_name.starts_with('[')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_dns_name;
+
+    /// The previous implementation of `is_valid_dns_name`, which checks every label separately.
+    fn is_valid_dns_name_by_label(name: &str) -> bool {
+        fn is_valid_dns_label(label: &str) -> bool {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        }
+
+        let name = name.strip_suffix('.').unwrap_or(name);
+        name.len() <= 253 && name.split('.').all(is_valid_dns_label)
+    }
+
+    #[test]
+    fn dns_name_same_as_by_label() {
+        let label = "a".repeat(63);
+        let long_names = [
+            format!("{}.com", label),
+            format!("{}a.com", label),
+            format!("{0}.{0}.{0}.{1}", label, "a".repeat(61)),
+            format!("{0}.{0}.{0}.{1}.", label, "a".repeat(61)),
+            format!("{0}.{0}.{0}.{1}", label, "a".repeat(62)),
+            format!("{0}-.com", "a".repeat(62)),
+        ];
+        let inputs = [
+            "",
+            ".",
+            "..",
+            "a",
+            "a.",
+            "a..",
+            ".a",
+            "a..b",
+            "example.com",
+            "example.com.",
+            ".example.com",
+            "example.com..",
+            "-example.com",
+            "example-.com",
+            "example.com-",
+            "matrix.-example.com",
+            "matrix-client.example.com",
+            "a.b-c.d0.1e",
+            "a--b.c",
+            "1.2.3.4",
+            "ex_ample.com",
+            "exämple.com",
+            "example.com:8448",
+            "user@example.com",
+            "example.com/path",
+            "EXAMPLE.COM",
+            "-",
+            "a.-",
+            "a-.",
+        ];
+
+        for name in inputs.iter().copied().chain(long_names.iter().map(String::as_str)) {
+            assert_eq!(is_valid_dns_name(name), is_valid_dns_name_by_label(name), "{}", name);
+        }
+    }
+}
@@ -30,12 +30,22 @@ pub use crate::{
     room_id::RoomId,
     room_id_or_room_alias_id::RoomIdOrAliasId,
     room_version_id::RoomVersionId,
-    server_name::{ServerName, ServerNameBox},
+    server_name::{
+        CaseInsensitiveServerName, DelegatedServerName, SemanticServerName, ServerName,
+        ServerNameBox, ServerNameInterner,
+    },
     signatures::{DeviceSignatures, EntitySignatures, ServerSignatures, Signatures},
     user_id::UserId,
 };
 #[doc(inline)]
-pub use ruma_identifiers_validation::error::Error;
+pub use ruma_identifiers_validation::{
+    error::{Error, ServerNameError},
+    server_name::validate as validate_server_name,
+};
+#[cfg(feature = "serde")]
+pub use server_name::deserialize_server_name_seq;
+#[cfg(feature = "std")]
+pub use server_name::NotAnIpLiteral;
 
 #[macro_use]
 mod macros;
@@ -18,6 +18,36 @@ pub struct MxcUri {
 }
 
 impl MxcUri {
+    /// Creates an MXC URI pointing to the media with the given ID on the given server.
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidMxcUri` will be returned if the media ID is empty or contains characters
+    /// other than ASCII letters, digits and `-`.
+    pub fn from_parts(server_name: &ServerName, media_id: &str) -> Result<Self, crate::Error> {
+        let uri = format!("mxc://{}/{}", server_name, media_id);
+        let slash_idx = validate(&uri)?;
+
+        Ok(Self { full_uri: uri.into(), slash_idx: Some(slash_idx) })
+    }
+
+    /// Parses a spec-compliant MXC URI, returning an error for any other string.
+    ///
+    /// Unlike the `From<&str>` and `From<String>` implementations, which accept any string so that
+    /// non-compliant URIs sent by other clients and servers can still be represented, this
+    /// rejects input that doesn't match `mxc://{serverName}/{mediaId}`. It is not a `TryFrom`
+    /// implementation because that would conflict with the existing `From` implementations.
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidMxcUri` will be returned if the scheme is not `mxc`, the server name is
+    /// invalid or the media ID is missing, empty or contains characters other than ASCII letters,
+    /// digits and `-`.
+    pub fn parse(uri: &str) -> Result<Self, crate::Error> {
+        let slash_idx = validate(uri)?;
+        Ok(Self { full_uri: uri.into(), slash_idx: Some(slash_idx) })
+    }
+
     /// If this is a valid MXC URI, returns the media ID.
     pub fn media_id(&self) -> Option<&str> {
         self.parts().map(|(_, s)| s)
@@ -38,11 +68,67 @@ impl MxcUri {
         })
     }
 
+    /// If this is a valid MXC URI, checks whether the host of its server name matches the given
+    /// glob pattern.
+    ///
+    /// This uses the same matching as the server ACL event content: `*` matches any sequence of
+    /// characters, `?` matches a single character, ASCII case is ignored and the port is not
+    /// taken into account. See [`ServerName::matches_glob_ignore_ascii_case`].
+    pub fn server_matches_glob(&self, pattern: &str) -> Option<bool> {
+        self.server_name().map(|server_name| server_name.matches_glob_ignore_ascii_case(pattern))
+    }
+
     /// Returns if this is a spec-compliant MXC URI.
     pub fn is_valid(&self) -> bool {
         self.slash_idx.is_some()
     }
 
+    /// Returns the path of the client-server API endpoint that downloads this media, like
+    /// `/_matrix/media/r0/download/{serverName}/{mediaId}`.
+    ///
+    /// The server name and media ID are percent-encoded, so the brackets and colons of IPv6
+    /// literals and ports don't end up in the path unescaped.
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidMxcUri` will be returned if this is not a spec-compliant MXC URI.
+    pub fn download_path(&self) -> Result<String, crate::Error> {
+        let (server_name, media_id) = self.parts().ok_or(crate::Error::InvalidMxcUri)?;
+        Ok(format!(
+            "/_matrix/media/r0/download/{}/{}",
+            percent_encode(server_name.as_str()),
+            percent_encode(media_id),
+        ))
+    }
+
+    /// Returns the path and query string of the client-server API endpoint that downloads a
+    /// thumbnail of this media, like
+    /// `/_matrix/media/r0/thumbnail/{serverName}/{mediaId}?width=…&height=…&method=…`.
+    ///
+    /// The server name and media ID are percent-encoded like in
+    /// [`download_path`](Self::download_path). The spec defines the methods `crop` and `scale`.
+    /// Other methods are percent-encoded as well.
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidMxcUri` will be returned if this is not a spec-compliant MXC URI.
+    pub fn thumbnail_path(
+        &self,
+        width: u32,
+        height: u32,
+        method: &str,
+    ) -> Result<String, crate::Error> {
+        let (server_name, media_id) = self.parts().ok_or(crate::Error::InvalidMxcUri)?;
+        Ok(format!(
+            "/_matrix/media/r0/thumbnail/{}/{}?width={}&height={}&method={}",
+            percent_encode(server_name.as_str()),
+            percent_encode(media_id),
+            width,
+            height,
+            percent_encode(method),
+        ))
+    }
+
     /// Create a string slice from this MXC URI.
     pub fn as_str(&self) -> &str {
         &self.full_uri
@@ -61,6 +147,20 @@ impl fmt::Display for MxcUri {
     }
 }
 
+/// Percent-encodes every byte of `s` that is not an unreserved character as per RFC 3986.
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
 fn from<S>(uri: S) -> MxcUri
 where
     S: AsRef<str> + Into<Box<str>>,
@@ -107,7 +207,7 @@ impl serde::Serialize for MxcUri {
 mod tests {
     use std::convert::TryFrom;
 
-    use crate::ServerName;
+    use crate::{Error, ServerName, UserId};
 
     use super::MxcUri;
 
@@ -125,6 +225,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mxc_uri_accessors() {
+        let mxc = MxcUri::from("mxc://matrix.org/abc123");
+
+        assert_eq!(mxc.server_name(), Some(<&ServerName>::try_from("matrix.org").unwrap()));
+        assert_eq!(mxc.media_id(), Some("abc123"));
+    }
+
+    #[test]
+    fn mxc_uri_accessors_with_port() {
+        let mxc = MxcUri::from("mxc://matrix.org:8448/abc123");
+
+        assert_eq!(mxc.server_name(), Some(<&ServerName>::try_from("matrix.org:8448").unwrap()));
+        assert_eq!(mxc.media_id(), Some("abc123"));
+    }
+
+    #[test]
+    fn mxc_uri_accessors_without_media_id() {
+        let mxc = MxcUri::from("mxc://matrix.org");
+
+        assert_eq!(mxc.server_name(), None);
+        assert_eq!(mxc.media_id(), None);
+    }
+
     #[test]
     fn parse_mxc_uri_without_media_id() {
         let mxc = MxcUri::from("mxc://127.0.0.1");
@@ -138,6 +262,99 @@ mod tests {
         assert!(!MxcUri::from("127.0.0.1/asd32asdfasdsd").is_valid());
     }
 
+    #[test]
+    fn parse_mxc_uri_with_empty_media_id() {
+        assert!(!MxcUri::from("mxc://127.0.0.1/").is_valid());
+    }
+
+    #[test]
+    fn server_matches_glob() {
+        let mxc = MxcUri::from("mxc://a.example/xyz");
+        assert_eq!(mxc.server_matches_glob("*.example"), Some(true));
+        assert_eq!(mxc.server_matches_glob("*.EXAMPLE"), Some(true));
+        assert_eq!(mxc.server_matches_glob("?.example"), Some(true));
+        assert_eq!(mxc.server_matches_glob("*.example.org"), Some(false));
+
+        let mxc = MxcUri::from("mxc://a.example:8448/xyz");
+        assert_eq!(mxc.server_matches_glob("*.example"), Some(true));
+
+        assert_eq!(MxcUri::from("mxc://a.example").server_matches_glob("*"), None);
+        assert_eq!(MxcUri::from("https://a.example/xyz").server_matches_glob("*"), None);
+    }
+
+    #[test]
+    fn parse_strict() {
+        let mxc = MxcUri::parse("mxc://matrix.org/abc123").unwrap();
+        assert!(mxc.is_valid());
+        assert_eq!(mxc.as_str(), "mxc://matrix.org/abc123");
+        assert_eq!(mxc.media_id(), Some("abc123"));
+
+        assert_eq!(MxcUri::parse("http://matrix.org/abc123"), Err(Error::InvalidMxcUri));
+        assert_eq!(MxcUri::parse(""), Err(Error::InvalidMxcUri));
+        assert_eq!(MxcUri::parse("mxc://matrix.org"), Err(Error::InvalidMxcUri));
+        assert_eq!(MxcUri::parse("mxc://matrix.org/"), Err(Error::InvalidMxcUri));
+        assert_eq!(MxcUri::parse("mxc://[test::1]/abc123"), Err(Error::InvalidMxcUri));
+    }
+
+    #[test]
+    fn from_parts() {
+        let user_id = UserId::try_from("@carl:example.com:8448").unwrap();
+        let mxc = MxcUri::from_parts(user_id.server_name(), "abc123").unwrap();
+
+        assert_eq!(mxc.as_str(), "mxc://example.com:8448/abc123");
+        assert!(mxc.is_valid());
+        assert_eq!(mxc.server_name(), Some(user_id.server_name()));
+        assert_eq!(mxc.media_id(), Some("abc123"));
+    }
+
+    #[test]
+    fn from_parts_invalid_media_id() {
+        let server_name = <&ServerName>::try_from("example.com").unwrap();
+
+        assert_eq!(MxcUri::from_parts(server_name, ""), Err(Error::InvalidMxcUri));
+        assert_eq!(MxcUri::from_parts(server_name, "abc/123"), Err(Error::InvalidMxcUri));
+        assert_eq!(MxcUri::from_parts(server_name, "abc 123"), Err(Error::InvalidMxcUri));
+    }
+
+    #[test]
+    fn download_path() {
+        assert_eq!(
+            MxcUri::from("mxc://matrix.org:8448/abc123").download_path().unwrap(),
+            "/_matrix/media/r0/download/matrix.org%3A8448/abc123"
+        );
+        assert_eq!(
+            MxcUri::from("mxc://[::1]/abc123").download_path().unwrap(),
+            "/_matrix/media/r0/download/%5B%3A%3A1%5D/abc123"
+        );
+    }
+
+    #[test]
+    fn thumbnail_path() {
+        let mxc = MxcUri::from("mxc://matrix.org/abc123");
+
+        assert_eq!(
+            mxc.thumbnail_path(64, 32, "crop").unwrap(),
+            "/_matrix/media/r0/thumbnail/matrix.org/abc123?width=64&height=32&method=crop"
+        );
+        assert_eq!(
+            mxc.thumbnail_path(800, 600, "a&b").unwrap(),
+            "/_matrix/media/r0/thumbnail/matrix.org/abc123?width=800&height=600&method=a%26b"
+        );
+        assert_eq!(
+            MxcUri::from("mxc://[::1]:8448/abc123").thumbnail_path(64, 32, "scale").unwrap(),
+            "/_matrix/media/r0/thumbnail/%5B%3A%3A1%5D%3A8448/abc123?width=64&height=32&method=scale"
+        );
+    }
+
+    #[test]
+    fn media_paths_invalid_mxc_uri() {
+        for uri in &["mxc://matrix.org", "mxc://matrix.org/", "http://matrix.org/abc123"] {
+            let mxc = MxcUri::from(*uri);
+            assert_eq!(mxc.download_path(), Err(Error::InvalidMxcUri));
+            assert_eq!(mxc.thumbnail_path(64, 64, "scale"), Err(Error::InvalidMxcUri));
+        }
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn serialize_mxc_uri() {
@@ -1,7 +1,29 @@
 //! Matrix-spec compliant server names.
-use std::{convert::TryFrom, fmt, mem, rc::Rc, str::FromStr, sync::Arc};
+use std::{
+    borrow::{Borrow, Cow},
+    cmp::Ordering,
+    collections::BTreeSet,
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    mem,
+    ops::Deref,
+    rc::Rc,
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+};
 
-use ruma_identifiers_validation::server_name::validate;
+#[cfg(feature = "std")]
+use std::{
+    error::Error as StdError,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
+};
+
+use ruma_identifiers_validation::{
+    server_name::{is_ip_literal, validate},
+    ServerNameError,
+};
 
 /// A Matrix-spec compliant server name.
 #[repr(transparent)]
@@ -13,7 +35,7 @@ pub struct ServerName(str);
 pub type ServerNameBox = Box<ServerName>;
 
 impl ServerName {
-    fn from_borrowed(s: &str) -> &Self {
+    pub(crate) fn from_borrowed(s: &str) -> &Self {
         unsafe { mem::transmute(s) }
     }
 
@@ -25,6 +47,65 @@ impl ServerName {
         unsafe { Box::from_raw(Box::into_raw(self) as _) }
     }
 
+    /// Tries to parse the given string slice as a borrowed `ServerName`.
+    ///
+    /// This is the same as `<&ServerName>::try_from(s)`.
+    pub fn parse(s: &str) -> Result<&Self, ServerNameError> {
+        validate(s)?;
+        Ok(Self::from_borrowed(s))
+    }
+
+    /// Tries to parse all of the given strings as owned `ServerName`s.
+    ///
+    /// The strings are validated in order. If one of them is invalid, its index and the reason
+    /// are returned, and the remaining strings are not looked at.
+    pub fn parse_all(
+        strings: impl IntoIterator<Item = String>,
+    ) -> Result<Vec<Box<Self>>, (usize, ServerNameError)> {
+        strings
+            .into_iter()
+            .enumerate()
+            .map(|(idx, s)| try_from(s).map_err(|err| (idx, err)))
+            .collect()
+    }
+
+    /// Tries to parse the given string as an owned `ServerName`, removing brackets around hosts
+    /// that are not IPv6 addresses.
+    ///
+    /// Brackets are only allowed around IPv6 literals, so a `ServerName` never needs this
+    /// normalization once it has been parsed. This takes the raw user input instead, turning
+    /// `[127.0.0.1]` into `127.0.0.1` and `[example.com]:8448` into `example.com:8448`, while
+    /// `[::1]` is kept as is.
+    ///
+    /// # Errors
+    ///
+    /// Bracketed hosts that contain a `:` but are not valid IPv6 addresses are rejected with
+    /// `ServerNameError::InvalidIpv6`, since it is unclear whether the `:` was meant to start a
+    /// port. This includes IPv6 addresses with a zone ID, like `[fe80::1%eth0]`. Unbracketed IPv6
+    /// addresses are rejected too, as it is likewise unclear where their port starts. Everything
+    /// else results in the same errors as [`parse`](Self::parse) after removing the brackets.
+    pub fn normalize_brackets(s: &str) -> Result<Box<Self>, ServerNameError> {
+        if !s.starts_with('[') || validate(s).is_ok() {
+            return try_from(s);
+        }
+
+        let end_of_host = s.find(']').ok_or(ServerNameError::InvalidIpv6)?;
+        let host = &s[1..end_of_host];
+        if host.contains(':') {
+            return Err(ServerNameError::InvalidIpv6);
+        }
+
+        try_from(format!("{}{}", host, &s[end_of_host + 1..]))
+    }
+
+    /// Tries to parse the given bytes as a borrowed `ServerName`.
+    ///
+    /// Bytes that are not valid UTF-8 result in `ServerNameError::InvalidUtf8`.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<&Self, ServerNameError> {
+        let s = std::str::from_utf8(bytes).map_err(|_| ServerNameError::InvalidUtf8)?;
+        Self::parse(s)
+    }
+
     /// Creates a string slice from this `ServerName`.
     pub fn as_str(&self) -> &str {
         &self.0
@@ -34,6 +115,268 @@ impl ServerName {
     pub fn as_bytes(&self) -> &[u8] {
         self.0.as_bytes()
     }
+
+    /// Returns the length of this `ServerName` in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether this `ServerName` is empty.
+    ///
+    /// Since empty server names are invalid, this always returns `false`. It exists for
+    /// consistency with string-like types.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the host of the server name.
+    ///
+    /// That is: Return the part of the server name before `:<port>` or the full server name if
+    /// there is no port. IPv6 literals keep their enclosing brackets.
+    pub fn host(&self) -> &str {
+        &self.0[..self.end_of_host()]
+    }
+
+    /// Returns a value that displays only the host of the server name, without the port.
+    ///
+    /// This is useful for showing a homeserver to users; the `Display` implementation of
+    /// `ServerName` itself prints the full server name, including the port.
+    pub fn display_host(&self) -> impl fmt::Display + '_ {
+        self.host()
+    }
+
+    /// Returns a value that formats the server name like its `Debug` implementation, but cut off
+    /// after `max` bytes.
+    ///
+    /// A cut-off server name is followed by `...`. This is useful to bound the size of log
+    /// messages containing server names from untrusted sources. The full server name is still
+    /// available through [`as_str`](Self::as_str) and the `Display` implementation.
+    pub fn debug_truncated(&self, max: usize) -> impl fmt::Debug + '_ {
+        TruncatedDebug { server_name: self, max }
+    }
+
+    /// Returns the port given explicitly in the server name, if any.
+    ///
+    /// Whether there is an explicit port matters for the [server name resolution] algorithm of the
+    /// federation API:
+    ///
+    /// * If the host is an IP literal, the server is contacted at that address, using the explicit
+    ///   port or 8448 if there is none.
+    /// * Otherwise, if there is an explicit port, the server is contacted at the host and that
+    ///   port. Neither `/.well-known/matrix/server` nor SRV records are looked up.
+    /// * Otherwise, `/.well-known/matrix/server` and then SRV records are used to find the actual
+    ///   address of the server.
+    ///
+    /// An explicit port of 8448 is still an explicit port, so it also skips these lookups.
+    ///
+    /// [server name resolution]: https://matrix.org/docs/spec/server_server/r0.1.4#resolving-server-names
+    pub fn port(&self) -> Option<u16> {
+        self.split_host_port().1
+    }
+
+    /// Resolves the server name to socket addresses, using port 8448 if it has no explicit port.
+    ///
+    /// IP literals are converted directly, without any DNS lookup. DNS names are resolved with
+    /// [`ToSocketAddrs`], which blocks until the lookup is done.
+    ///
+    /// This is meant for connecting to a server directly. It does not implement the full
+    /// [server name resolution] algorithm of the federation API, i.e. DNS names without a port are
+    /// not looked up via `/.well-known/matrix/server` or SRV records.
+    ///
+    /// [server name resolution]: https://matrix.org/docs/spec/server_server/r0.1.4#resolving-server-names
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn try_into_socket_addrs(&self) -> io::Result<impl Iterator<Item = SocketAddr>> {
+        let (host, port) = self.split_host_port();
+        // `ToSocketAddrs` expects IPv6 addresses without brackets, and only does a DNS lookup if
+        // the host doesn't parse as an IP address.
+        let host = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+
+        (host, port.unwrap_or(8448)).to_socket_addrs()
+    }
+
+    /// Checks whether the server name is an IP literal for the address and port of `addr`.
+    ///
+    /// The server name has to have an explicit port that is the same as the port of `addr`; an IP
+    /// literal without a port doesn't match any `SocketAddr`, not even one with port 8448. IPv4
+    /// literals only match `SocketAddr::V4`, not IPv6 addresses that embed an IPv4 address, and
+    /// the flow info and scope ID of a `SocketAddr::V6` are ignored.
+    ///
+    /// DNS names always return `false`, since they can't be compared to an address without
+    /// resolving them.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn matches_socket_addr(&self, addr: &SocketAddr) -> bool {
+        IpAddr::try_from(self)
+            .map_or(false, |ip_addr| ip_addr == addr.ip() && self.port() == Some(addr.port()))
+    }
+
+    /// Returns a copy of this server name with the given port, replacing the existing port if
+    /// there is one.
+    ///
+    /// IPv6 literals keep their enclosing brackets, so `[::1]` becomes `[::1]:8448`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ServerNameError::InvalidPort` if `port` is `0`, and
+    /// `ServerNameError::MaximumLengthExceeded` if the resulting server name would be longer than
+    /// 255 bytes.
+    pub fn with_port(&self, port: u16) -> Result<Box<ServerName>, ServerNameError> {
+        try_from(format!("{}:{}", self.host(), port))
+    }
+
+    /// Returns both the host and the port of the server name.
+    ///
+    /// This is equivalent to `(self.host(), self.port())`, but only scans the server name once.
+    pub fn split_host_port(&self) -> (&str, Option<u16>) {
+        let end_of_host = self.end_of_host();
+
+        let port = if end_of_host == self.0.len() {
+            None
+        } else {
+            // Validation guarantees that the host is followed by `:` and a valid port.
+            Some(self.0[end_of_host + 1..].parse().unwrap())
+        };
+
+        (&self.0[..end_of_host], port)
+    }
+
+    /// Returns the server name in a form that can be used as the authority of a URL, like
+    /// `https://{authority}/_matrix/...`.
+    ///
+    /// Valid server names only contain characters that are allowed in a URL authority, so no
+    /// escaping is needed and this is the same as [`as_str`](Self::as_str). In particular, IPv6
+    /// literals keep their enclosing brackets, which must not be stripped when building a URL.
+    pub fn to_uri_authority(&self) -> &str {
+        self.as_str()
+    }
+
+    /// Returns the server name with punycode-encoded labels of the host decoded to Unicode.
+    ///
+    /// Internationalized domain names are only valid in a server name in their punycode form, like
+    /// `xn--bcher-kva.example`. This decodes such labels (`bücher.example`) for display purposes;
+    /// it must not be used to compare or connect to servers. Labels that are not valid punycode
+    /// are kept as-is, as is the port.
+    pub fn to_unicode(&self) -> Cow<'_, str> {
+        let host = self.host();
+        if self.is_ip_literal() || !host.split('.').any(is_punycode_label) {
+            return Cow::Borrowed(self.as_str());
+        }
+
+        let mut unicode = String::with_capacity(self.0.len());
+        for (idx, label) in host.split('.').enumerate() {
+            if idx != 0 {
+                unicode.push('.');
+            }
+
+            let decoded =
+                if is_punycode_label(label) { decode_punycode(&label[4..]) } else { None };
+            unicode.push_str(decoded.as_deref().unwrap_or(label));
+        }
+        unicode.push_str(&self.0[host.len()..]);
+
+        Cow::Owned(unicode)
+    }
+
+    /// Returns the server name without the trailing dot of a fully-qualified DNS name.
+    ///
+    /// A DNS name can be written with a trailing dot to mark it as fully-qualified, like
+    /// `example.com.` or `example.com.:8080`. Such server names are valid, but refer to the same
+    /// server as the name without the dot, so this can be used to normalize them.
+    pub fn without_trailing_dot(&self) -> Cow<'_, ServerName> {
+        let (host, rest) = self.0.split_at(self.end_of_host());
+        match host.strip_suffix('.') {
+            Some(stripped) if rest.is_empty() => Cow::Borrowed(Self::from_borrowed(stripped)),
+            Some(stripped) => Cow::Owned(Self::from_owned(format!("{}{}", stripped, rest).into())),
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// Returns true if and only if the host of the server name is an IPv4 or IPv6 address.
+    pub fn is_ip_literal(&self) -> bool {
+        is_ip_literal(self.host())
+    }
+
+    /// Returns an iterator over the dot-separated labels of the host of the server name, from left
+    /// to right.
+    ///
+    /// The port is ignored, and so is the trailing dot of a fully-qualified DNS name, so
+    /// `matrix.example.com.:8448` yields `matrix`, `example` and `com`. IP literals don't consist
+    /// of DNS labels, so the iterator is empty for them, even for IPv4 addresses.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        let host = if self.is_ip_literal() { "" } else { self.host() };
+        host.split_terminator('.')
+    }
+
+    /// Checks whether this server name is equal to `other`, ignoring ASCII case differences.
+    ///
+    /// DNS names are case-insensitive, so this is the right comparison for deduplicating server
+    /// names. Ports never contain letters, so they still have to match exactly.
+    pub fn eq_ignore_ascii_case(&self, other: &ServerName) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+
+    /// Checks whether this server name is equal to `other`, treating a missing port as the given
+    /// default port.
+    ///
+    /// With a `default` of 8448, the port used for federation when none is given, this considers
+    /// `example.com` and `example.com:8448` equal. Hosts are compared exactly, use
+    /// [`to_ascii_lowercase`](Self::to_ascii_lowercase) first to ignore case differences.
+    pub fn eq_with_default_port(&self, other: &ServerName, default: u16) -> bool {
+        let (host, port) = self.split_host_port();
+        let (other_host, other_port) = other.split_host_port();
+
+        host == other_host && port.unwrap_or(default) == other_port.unwrap_or(default)
+    }
+
+    /// Compares this server name to `other` by host first and then by numeric port, with a
+    /// missing port ordered before any port.
+    ///
+    /// Unlike the `Ord` implementation, which compares the full strings byte-wise, this orders
+    /// `example.com:20` before `example.com:100`, which is more useful for displaying sorted
+    /// server lists.
+    pub fn cmp_semantic(&self, other: &ServerName) -> Ordering {
+        let (host, port) = self.split_host_port();
+        let (other_host, other_port) = other.split_host_port();
+
+        host.cmp(other_host).then(port.cmp(&other_port))
+    }
+
+    /// Returns a copy of this server name with the host converted to ASCII lowercase.
+    pub fn to_ascii_lowercase(&self) -> Box<ServerName> {
+        Self::from_owned(self.0.to_ascii_lowercase().into())
+    }
+
+    /// Checks whether the host of the server name matches the given glob pattern.
+    ///
+    /// The port is ignored. In the pattern, `*` matches zero or more characters and `?` matches
+    /// exactly one character, like in the `allow` and `deny` lists of `m.room.server_acl` events.
+    ///
+    /// The comparison is case-sensitive, see
+    /// [`matches_glob_ignore_ascii_case`](Self::matches_glob_ignore_ascii_case) for a variant that
+    /// follows DNS semantics.
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        matches_glob(pattern, self.host(), false)
+    }
+
+    /// Checks whether the host of the server name matches the given glob pattern, ignoring ASCII
+    /// case differences.
+    ///
+    /// This is like [`matches_glob`](Self::matches_glob), except that `*.Example.COM` matches
+    /// `host.example.com`, like DNS names are compared.
+    pub fn matches_glob_ignore_ascii_case(&self, pattern: &str) -> bool {
+        matches_glob(pattern, self.host(), true)
+    }
+
+    /// Returns the byte index at which the host part of the server name ends.
+    fn end_of_host(&self) -> usize {
+        if self.0.starts_with('[') {
+            // IPv6 literals are always enclosed in brackets, so the host ends after the `]`.
+            self.0.find(']').unwrap() + 1
+        } else {
+            self.0.find(':').unwrap_or(self.0.len())
+        }
+    }
 }
 
 impl fmt::Debug for ServerName {
@@ -42,6 +385,23 @@ impl fmt::Debug for ServerName {
     }
 }
 
+struct TruncatedDebug<'a> {
+    server_name: &'a ServerName,
+    max: usize,
+}
+
+impl fmt::Debug for TruncatedDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.server_name.as_str();
+        if s.len() <= self.max {
+            return f.write_str(s);
+        }
+
+        // Valid server names only consist of ASCII characters, so every index is a char boundary.
+        write!(f, "{}...", &s[..self.max])
+    }
+}
+
 impl Clone for Box<ServerName> {
     fn clone(&self) -> Self {
         (**self).to_owned()
@@ -56,6 +416,57 @@ impl ToOwned for ServerName {
     }
 }
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl TryFrom<&ServerName> for IpAddr {
+    type Error = NotAnIpLiteral;
+
+    /// Returns the IP address of the server name, without the port.
+    ///
+    /// Fails if the host of the server name is a DNS name.
+    fn try_from(server_name: &ServerName) -> Result<Self, Self::Error> {
+        let host = server_name.host();
+        let ip_addr = match host.strip_prefix('[') {
+            // Validation guarantees that IPv6 literals end with `]`.
+            Some(ipv6) => ipv6[..ipv6.len() - 1].parse::<Ipv6Addr>().map(IpAddr::V6),
+            None => host.parse::<Ipv4Addr>().map(IpAddr::V4),
+        };
+
+        ip_addr.map_err(|_| NotAnIpLiteral)
+    }
+}
+
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+impl TryFrom<&ServerName> for http::uri::Authority {
+    type Error = http::uri::InvalidUri;
+
+    /// Converts the server name to a URI authority, keeping the brackets of IPv6 literals and the
+    /// port.
+    ///
+    /// Every valid server name is also a valid authority, so this is not expected to fail.
+    fn try_from(server_name: &ServerName) -> Result<Self, Self::Error> {
+        server_name.as_str().parse()
+    }
+}
+
+/// An error returned when converting a [`ServerName`] whose host is a DNS name to an [`IpAddr`].
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct NotAnIpLiteral;
+
+#[cfg(feature = "std")]
+impl fmt::Display for NotAnIpLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("server name is not an IP address literal")
+    }
+}
+
+#[cfg(feature = "std")]
+impl StdError for NotAnIpLiteral {}
+
 impl From<&ServerName> for Box<ServerName> {
     fn from(s: &ServerName) -> Self {
         s.to_owned()
@@ -76,7 +487,132 @@ impl From<&ServerName> for Arc<ServerName> {
     }
 }
 
-fn try_from<S>(server_name: S) -> Result<Box<ServerName>, crate::Error>
+/// Checks whether `s` matches the glob `pattern`, where `*` matches zero or more characters and `?`
+/// matches exactly one character.
+fn matches_glob(pattern: &str, s: &str, ignore_ascii_case: bool) -> bool {
+    // Byte indices into `pattern` and `s`.
+    let (mut p_idx, mut s_idx) = (0, 0);
+    // Where to resume matching if the last `*` should swallow one more character of `s`.
+    let mut backtrack = None;
+
+    loop {
+        match pattern[p_idx..].chars().next() {
+            Some('*') => {
+                p_idx += 1;
+                backtrack = Some((p_idx, s_idx));
+                continue;
+            }
+            Some(p_char) => {
+                if let Some(s_char) = s[s_idx..].chars().next() {
+                    if p_char == '?'
+                        || p_char == s_char
+                        || (ignore_ascii_case && p_char.eq_ignore_ascii_case(&s_char))
+                    {
+                        p_idx += p_char.len_utf8();
+                        s_idx += s_char.len_utf8();
+                        continue;
+                    }
+                }
+            }
+            None if s_idx == s.len() => return true,
+            None => {}
+        }
+
+        match backtrack {
+            Some((star_p_idx, star_s_idx)) if star_s_idx < s.len() => {
+                let skipped = s[star_s_idx..].chars().next().unwrap();
+                p_idx = star_p_idx;
+                s_idx = star_s_idx + skipped.len_utf8();
+                backtrack = Some((p_idx, s_idx));
+            }
+            _ => return false,
+        }
+    }
+}
+
+fn is_punycode_label(label: &str) -> bool {
+    label.len() > 4 && label[..4].eq_ignore_ascii_case("xn--")
+}
+
+/// Decodes a punycode string (without the `xn--` prefix) as specified in [RFC 3492].
+///
+/// Returns `None` if the input is not valid punycode.
+///
+/// [RFC 3492]: https://tools.ietf.org/html/rfc3492
+fn decode_punycode(input: &str) -> Option<String> {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+    const SKEW: u32 = 38;
+    const DAMP: u32 = 700;
+
+    fn adapt(mut delta: u32, num_points: u32, first_time: bool) -> u32 {
+        delta /= if first_time { DAMP } else { 2 };
+        delta += delta / num_points;
+
+        let mut k = 0;
+        while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+
+        k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+    }
+
+    // Everything before the last `-` is copied verbatim, the rest encodes the non-ASCII chars.
+    let (basic, extended) = match input.rfind('-') {
+        Some(idx) => (&input[..idx], &input[idx + 1..]),
+        None => ("", input),
+    };
+
+    let mut output: Vec<char> = basic.chars().collect();
+    let (mut n, mut i, mut bias) = (128_u32, 0_u32, 72_u32);
+    let mut digits = extended.bytes().peekable();
+
+    while digits.peek().is_some() {
+        let old_i = i;
+        let mut weight = 1_u32;
+        let mut k = BASE;
+
+        loop {
+            let digit = match digits.next()? {
+                byte @ b'a'..=b'z' => byte - b'a',
+                byte @ b'A'..=b'Z' => byte - b'A',
+                byte @ b'0'..=b'9' => byte - b'0' + 26,
+                _ => return None,
+            } as u32;
+
+            i = i.checked_add(digit.checked_mul(weight)?)?;
+
+            let t = if k <= bias {
+                T_MIN
+            } else if k >= bias + T_MAX {
+                T_MAX
+            } else {
+                k - bias
+            };
+
+            if digit < t {
+                break;
+            }
+
+            weight = weight.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+
+        let num_points = output.len() as u32 + 1;
+        bias = adapt(i - old_i, num_points, old_i == 0);
+        n = n.checked_add(i / num_points)?;
+        i %= num_points;
+
+        output.insert(i as usize, std::char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+fn try_from<S>(server_name: S) -> Result<Box<ServerName>, ServerNameError>
 where
     S: AsRef<str> + Into<Box<str>>,
 {
@@ -96,6 +632,20 @@ impl AsRef<str> for Box<ServerName> {
     }
 }
 
+// `Hash`, `Eq` and `Ord` are derived, so they behave exactly like the ones of `str`, as required
+// by `Borrow`.
+impl Borrow<str> for ServerName {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for Box<ServerName> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
 impl From<Box<ServerName>> for String {
     fn from(s: Box<ServerName>) -> Self {
         s.into_owned().into()
@@ -103,16 +653,15 @@ impl From<Box<ServerName>> for String {
 }
 
 impl<'a> TryFrom<&'a str> for &'a ServerName {
-    type Error = crate::Error;
+    type Error = ServerNameError;
 
     fn try_from(server_name: &'a str) -> Result<Self, Self::Error> {
-        validate(server_name)?;
-        Ok(ServerName::from_borrowed(server_name))
+        ServerName::parse(server_name)
     }
 }
 
 impl FromStr for Box<ServerName> {
-    type Err = crate::Error;
+    type Err = ServerNameError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         try_from(s)
@@ -120,7 +669,7 @@ impl FromStr for Box<ServerName> {
 }
 
 impl TryFrom<&str> for Box<ServerName> {
-    type Error = crate::Error;
+    type Error = ServerNameError;
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         try_from(s)
@@ -128,91 +677,1657 @@ impl TryFrom<&str> for Box<ServerName> {
 }
 
 impl TryFrom<String> for Box<ServerName> {
-    type Error = crate::Error;
+    type Error = ServerNameError;
 
     fn try_from(s: String) -> Result<Self, Self::Error> {
         try_from(s)
     }
 }
 
+impl TryFrom<&[u8]> for Box<ServerName> {
+    type Error = ServerNameError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        ServerName::try_from_bytes(bytes).map(ToOwned::to_owned)
+    }
+}
+
+impl TryFrom<Vec<u8>> for Box<ServerName> {
+    type Error = ServerNameError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        let s = String::from_utf8(bytes).map_err(|_| ServerNameError::InvalidUtf8)?;
+        try_from(s)
+    }
+}
+
 impl fmt::Display for ServerName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_str())
     }
 }
 
+/// If you activate the `compat` feature, integers are accepted too and interpreted as IPv4
+/// addresses, e.g. `2130706433` is deserialized as `127.0.0.1`. Maps with a `host` and an
+/// optional `port` entry are accepted as well, e.g. `{ "host": "::1", "port": 8448 }` is
+/// deserialized as `[::1]:8448`.
 #[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for Box<ServerName> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        crate::deserialize_id(deserializer, "An IP address or hostname")
+        #[cfg(not(feature = "compat"))]
+        return crate::deserialize_id(deserializer, "An IP address or hostname");
+
+        #[cfg(feature = "compat")]
+        return deserializer.deserialize_any(CompatServerNameVisitor);
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "compat"))]
+struct CompatServerNameVisitor;
+
+#[cfg(all(feature = "serde", feature = "compat"))]
+impl<'de> serde::de::Visitor<'de> for CompatServerNameVisitor {
+    type Value = Box<ServerName>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(
+            "An IP address or hostname, an IPv4 address as an integer, or a map with a host and a \
+             port",
+        )
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        try_from(v).map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        let ip = u32::try_from(v)
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))?;
+        Ok(ServerName::from_owned(std::net::Ipv4Addr::from(ip).to_string().into()))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        use serde::de::{Error, IgnoredAny, Unexpected};
+
+        let mut host: Option<String> = None;
+        let mut port: Option<u16> = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "host" if host.is_some() => return Err(Error::duplicate_field("host")),
+                "host" => host = Some(map.next_value()?),
+                "port" if port.is_some() => return Err(Error::duplicate_field("port")),
+                "port" => port = map.next_value()?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        let host = host.ok_or_else(|| Error::missing_field("host"))?;
+        // IPv6 addresses may be given without the brackets that a server name requires.
+        let host =
+            if host.contains(':') && !host.starts_with('[') { format!("[{}]", host) } else { host };
+        let server_name = match port {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        };
+
+        try_from(server_name.as_str())
+            .map_err(|_| Error::invalid_value(Unexpected::Str(&server_name), &self))
+    }
+}
+
+/// Deserializes a `&ServerName` borrowed from the input, without allocating.
+///
+/// Like for `&str`, this fails if the server name can't be borrowed from the input, for example
+/// because it contains escape sequences in JSON.
+#[cfg(feature = "serde")]
+impl<'de: 'a, 'a> serde::Deserialize<'de> for &'a ServerName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, Unexpected};
+
+        let s = <&'a str>::deserialize(deserializer)?;
+        <&ServerName>::try_from(s)
+            .map_err(|_| Error::invalid_value(Unexpected::Str(s), &"An IP address or hostname"))
+    }
+}
+
+/// Deserializes a sequence of server names, borrowing them from the input where possible.
+///
+/// Every entry is validated, but only entries that can't be borrowed from the input, e.g. because
+/// they contain escape sequences in JSON, are copied into a `Box<ServerName>`. If an entry is
+/// invalid, the error message contains its index in the sequence.
+///
+/// This can be used with `#[serde(borrow, deserialize_with = "...")]` on a field of type
+/// `Vec<Cow<'a, ServerName>>`.
+#[cfg(feature = "serde")]
+pub fn deserialize_server_name_seq<'de, D>(
+    deserializer: D,
+) -> Result<Vec<Cow<'de, ServerName>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{DeserializeSeed, Error, SeqAccess, Unexpected, Visitor};
+
+    struct ElementVisitor {
+        index: usize,
     }
+
+    impl ElementVisitor {
+        fn validate<E: Error>(&self, s: &str) -> Result<(), E> {
+            validate(s).map_err(|err| {
+                E::custom(format_args!("invalid server name at index {}: {}", self.index, err))
+            })
+        }
+    }
+
+    impl<'de> Visitor<'de> for ElementVisitor {
+        type Value = Cow<'de, ServerName>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a server name at index {}", self.index)
+        }
+
+        fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+            self.validate(v)?;
+            Ok(Cow::Borrowed(ServerName::from_borrowed(v)))
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            self.validate(v)?;
+            Ok(Cow::Owned(ServerName::from_owned(v.into())))
+        }
+
+        fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+            self.validate(&v)?;
+            Ok(Cow::Owned(ServerName::from_owned(v.into())))
+        }
+
+        fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            let s = std::str::from_utf8(v)
+                .map_err(|_| E::invalid_value(Unexpected::Bytes(v), &self))?;
+            self.visit_str(s)
+        }
+    }
+
+    impl<'de> DeserializeSeed<'de> for ElementVisitor {
+        type Value = Cow<'de, ServerName>;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            deserializer.deserialize_str(self)
+        }
+    }
+
+    struct SeqVisitor;
+
+    impl<'de> Visitor<'de> for SeqVisitor {
+        type Value = Vec<Cow<'de, ServerName>>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a sequence of server names")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut server_names = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(server_name) =
+                seq.next_element_seed(ElementVisitor { index: server_names.len() })?
+            {
+                server_names.push(server_name);
+            }
+
+            Ok(server_names)
+        }
+    }
+
+    deserializer.deserialize_seq(SeqVisitor)
 }
 
 partial_eq_string!(ServerName);
 partial_eq_string!(Box<ServerName>);
 
-#[cfg(test)]
-mod tests {
-    use std::convert::TryFrom;
+/// A borrowed `ServerName` that is compared, ordered and hashed ignoring ASCII case.
+///
+/// This is useful as the key of a map or set that should treat server names that only differ in
+/// the case of the host, like `Example.com` and `example.com`, as the same server. Neither
+/// construction nor comparison allocates.
+#[derive(Clone, Copy, Debug)]
+pub struct CaseInsensitiveServerName<'a>(&'a ServerName);
 
-    use super::ServerName;
+impl<'a> CaseInsensitiveServerName<'a> {
+    /// Wraps the given `ServerName`.
+    pub fn new(server_name: &'a ServerName) -> Self {
+        Self(server_name)
+    }
 
-    #[test]
-    fn ipv4_host() {
-        assert!(<&ServerName>::try_from("127.0.0.1").is_ok());
+    /// Returns the wrapped `ServerName`, with its original case.
+    pub fn as_server_name(&self) -> &'a ServerName {
+        self.0
     }
 
-    #[test]
-    fn ipv4_host_and_port() {
-        assert!(<&ServerName>::try_from("1.1.1.1:12000").is_ok());
+    fn lowercase_bytes(&self) -> impl Iterator<Item = u8> + 'a {
+        self.0.as_bytes().iter().map(u8::to_ascii_lowercase)
     }
+}
 
-    #[test]
-    fn ipv6() {
-        assert!(<&ServerName>::try_from("[::1]").is_ok());
+impl<'a> From<&'a ServerName> for CaseInsensitiveServerName<'a> {
+    fn from(server_name: &'a ServerName) -> Self {
+        Self::new(server_name)
     }
+}
 
-    #[test]
-    fn ipv6_with_port() {
-        assert!(<&ServerName>::try_from("[1234:5678::abcd]:5678").is_ok());
+impl PartialEq for CaseInsensitiveServerName<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(other.0)
     }
+}
 
-    #[test]
-    fn dns_name() {
-        assert!(<&ServerName>::try_from("example.com").is_ok());
+impl Eq for CaseInsensitiveServerName<'_> {}
+
+impl PartialOrd for CaseInsensitiveServerName<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    #[test]
-    fn dns_name_with_port() {
-        assert!(<&ServerName>::try_from("ruma.io:8080").is_ok());
+impl Ord for CaseInsensitiveServerName<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lowercase_bytes().cmp(other.lowercase_bytes())
     }
+}
 
-    #[test]
-    fn empty_string() {
-        assert!(<&ServerName>::try_from("").is_err());
+impl Hash for CaseInsensitiveServerName<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.lowercase_bytes() {
+            state.write_u8(byte);
+        }
+        // Same terminator as `str`'s `Hash` implementation, so prefixes hash differently.
+        state.write_u8(0xff);
     }
+}
 
-    #[test]
-    fn invalid_ipv6() {
-        assert!(<&ServerName>::try_from("[test::1]").is_err());
+/// An owned `ServerName` that is compared, ordered and hashed by host first and then by numeric
+/// port, like [`ServerName::cmp_semantic`].
+///
+/// This is useful as the key of a `BTreeMap` or `BTreeSet` that should iterate over
+/// `example.com:20` before `example.com:100`, while the `Ord` implementation of `ServerName`
+/// compares the full strings byte-wise. A missing port is ordered before any port.
+#[derive(Clone, Debug)]
+pub struct SemanticServerName(Box<ServerName>);
+
+impl SemanticServerName {
+    /// Wraps the given `ServerName`.
+    pub fn new(server_name: Box<ServerName>) -> Self {
+        Self(server_name)
     }
 
-    #[test]
-    fn ipv4_with_invalid_port() {
-        assert!(<&ServerName>::try_from("127.0.0.1:").is_err());
+    /// Returns the wrapped `ServerName`.
+    pub fn as_server_name(&self) -> &ServerName {
+        &self.0
     }
 
-    #[test]
-    fn ipv6_with_invalid_port() {
-        assert!(<&ServerName>::try_from("[fe80::1]:100000").is_err());
-        assert!(<&ServerName>::try_from("[fe80::1]!").is_err());
+    /// Unwraps the `ServerName`.
+    pub fn into_server_name(self) -> Box<ServerName> {
+        self.0
     }
+}
 
-    #[test]
-    fn dns_name_with_invalid_port() {
-        assert!(<&ServerName>::try_from("matrix.org:hello").is_err());
+impl From<Box<ServerName>> for SemanticServerName {
+    fn from(server_name: Box<ServerName>) -> Self {
+        Self::new(server_name)
+    }
+}
+
+impl PartialEq for SemanticServerName {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SemanticServerName {}
+
+impl PartialOrd for SemanticServerName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemanticServerName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp_semantic(&other.0)
+    }
+}
+
+impl Hash for SemanticServerName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hash the same parts that `Ord` compares.
+        self.0.split_host_port().hash(state);
+    }
+}
+
+/// An owned `ServerName` that was obtained through `.well-known` delegation.
+///
+/// Server-server requests are delegated at most once: the server name from the
+/// `/.well-known/matrix/server` file of a server is used as is, without looking up its own
+/// `.well-known` file. Wrapping it in this type lets routing code tell such server names apart
+/// from those that may still be delegated. It dereferences to the wrapped `ServerName`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DelegatedServerName(Box<ServerName>);
+
+impl DelegatedServerName {
+    /// Marks the given `ServerName` as obtained through delegation.
+    pub fn new(server_name: Box<ServerName>) -> Self {
+        Self(server_name)
+    }
+
+    /// Returns the wrapped `ServerName`.
+    pub fn as_server_name(&self) -> &ServerName {
+        &self.0
+    }
+
+    /// Unwraps the `ServerName`.
+    pub fn into_server_name(self) -> Box<ServerName> {
+        self.0
+    }
+}
+
+impl Deref for DelegatedServerName {
+    type Target = ServerName;
+
+    fn deref(&self) -> &ServerName {
+        &self.0
+    }
+}
+
+impl AsRef<ServerName> for DelegatedServerName {
+    fn as_ref(&self) -> &ServerName {
+        &self.0
+    }
+}
+
+impl From<Box<ServerName>> for DelegatedServerName {
+    fn from(server_name: Box<ServerName>) -> Self {
+        Self::new(server_name)
+    }
+}
+
+impl From<DelegatedServerName> for Box<ServerName> {
+    fn from(server_name: DelegatedServerName) -> Self {
+        server_name.into_server_name()
+    }
+}
+
+/// A set of shared server names that deduplicates equal server names.
+///
+/// In large federation graphs, the same server names show up over and over again. Interning them
+/// makes all occurrences share a single allocation. Server names are never removed from the
+/// interner, so it should be dropped once it is no longer needed.
+#[derive(Debug, Default)]
+pub struct ServerNameInterner {
+    server_names: Mutex<BTreeSet<Arc<ServerName>>>,
+}
+
+impl ServerNameInterner {
+    /// Creates an empty `ServerNameInterner`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared server name that is equal to the given one.
+    ///
+    /// The server name is only copied if it hasn't been interned before.
+    pub fn intern(&self, server_name: &ServerName) -> Arc<ServerName> {
+        // Inserting can't leave the set in an inconsistent state, so poisoning can be ignored.
+        let mut server_names = self.server_names.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(interned) = server_names.get(server_name) {
+            return interned.clone();
+        }
+
+        let interned = Arc::<ServerName>::from(server_name);
+        server_names.insert(interned.clone());
+        interned
+    }
+
+    /// Returns the number of distinct server names that were interned.
+    pub fn len(&self) -> usize {
+        self.server_names.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Returns `true` if no server names were interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::{Borrow, Cow},
+        cmp::Ordering,
+        collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet},
+        convert::TryFrom,
+        hash::{Hash, Hasher},
+        rc::Rc,
+        sync::Arc,
+    };
+
+    use super::{
+        decode_punycode, CaseInsensitiveServerName, DelegatedServerName, SemanticServerName,
+        ServerName, ServerNameInterner,
+    };
+    use crate::{Error, ServerNameError};
+
+    #[test]
+    fn ipv4_host() {
+        assert!(<&ServerName>::try_from("127.0.0.1").is_ok());
+    }
+
+    #[test]
+    fn ipv4_host_and_port() {
+        assert!(<&ServerName>::try_from("1.1.1.1:12000").is_ok());
+    }
+
+    #[test]
+    fn ipv6() {
+        assert!(<&ServerName>::try_from("[::1]").is_ok());
+    }
+
+    #[test]
+    fn ipv6_with_port() {
+        assert!(<&ServerName>::try_from("[1234:5678::abcd]:5678").is_ok());
+    }
+
+    #[test]
+    fn dns_name() {
+        assert!(<&ServerName>::try_from("example.com").is_ok());
+    }
+
+    #[test]
+    fn dns_name_with_port() {
+        assert!(<&ServerName>::try_from("ruma.io:8080").is_ok());
+    }
+
+    #[test]
+    fn dns_name_with_trailing_dot() {
+        assert!(<&ServerName>::try_from("example.com.").is_ok());
+        assert!(<&ServerName>::try_from("example.com.:8080").is_ok());
+    }
+
+    #[test]
+    fn without_trailing_dot() {
+        let server_name = <&ServerName>::try_from("example.com.").unwrap();
+        assert_eq!(server_name.host(), "example.com.");
+        assert_eq!(server_name.without_trailing_dot().as_str(), "example.com");
+        assert!(matches!(server_name.without_trailing_dot(), Cow::Borrowed(_)));
+
+        let server_name = <&ServerName>::try_from("example.com.:8080").unwrap();
+        assert_eq!(server_name.host(), "example.com.");
+        assert_eq!(server_name.port(), Some(8080));
+        assert_eq!(server_name.without_trailing_dot().as_str(), "example.com:8080");
+
+        for &s in &["example.com", "example.com:8080", "1.2.3.4", "[::1]:8448"] {
+            let server_name = <&ServerName>::try_from(s).unwrap();
+            assert_eq!(server_name.without_trailing_dot().as_str(), s);
+        }
+    }
+
+    #[test]
+    fn empty_string() {
+        assert!(<&ServerName>::try_from("").is_err());
+    }
+
+    #[test]
+    fn invalid_ipv6() {
+        assert!(<&ServerName>::try_from("[test::1]").is_err());
+    }
+
+    #[test]
+    fn ipv4_with_invalid_port() {
+        assert!(<&ServerName>::try_from("127.0.0.1:").is_err());
+    }
+
+    #[test]
+    fn ipv6_with_zone_id() {
+        for &s in &[
+            "[fe80::1%eth0]",
+            "[fe80::1%eth0]:8448",
+            "[fe80::1%25eth0]",
+            "[fe80::1%25eth0]:8448",
+            "[fe80::1%1]",
+            "[fe80::1%1]:8448",
+        ] {
+            assert_eq!(<&ServerName>::try_from(s), Err(ServerNameError::InvalidIpv6), "{}", s);
+        }
+    }
+
+    #[test]
+    fn ipv6_with_invalid_port() {
+        assert!(<&ServerName>::try_from("[fe80::1]:100000").is_err());
+        assert!(<&ServerName>::try_from("[fe80::1]!").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_borrowed() {
+        let json = r#""example.com:8448""#;
+        let server_name = serde_json::from_str::<&ServerName>(json).unwrap();
+
+        assert_eq!(server_name, "example.com:8448");
+        // The server name points into the input instead of a new allocation.
+        assert_eq!(server_name.as_str().as_ptr(), json[1..].as_ptr());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_borrowed_invalid() {
+        assert!(serde_json::from_str::<&ServerName>(r#""""#).is_err());
+        assert!(serde_json::from_str::<&ServerName>(r#""[test::1]""#).is_err());
+        assert!(serde_json::from_str::<&ServerName>(r#""matrix.org:hello""#).is_err());
+    }
+
+    #[test]
+    fn dns_name_with_invalid_port() {
+        assert!(<&ServerName>::try_from("matrix.org:hello").is_err());
+    }
+
+    #[test]
+    fn eq_str() {
+        let server_name = <&ServerName>::try_from("example.com").unwrap();
+        assert!(server_name == "example.com");
+        assert!("example.com" == server_name);
+        assert!(*server_name == "example.com");
+        assert!("example.com" == *server_name);
+        assert!(*server_name == *"example.com");
+        assert!(server_name != "matrix.org");
+        assert!("matrix.org" != server_name);
+
+        let server_name = server_name.to_owned();
+        assert!(server_name == "example.com");
+        assert!("example.com" == server_name);
+        assert!(server_name == *"example.com");
+        assert!(*"example.com" == server_name);
+        assert!(server_name != "matrix.org");
+        assert!("matrix.org" != server_name);
+    }
+
+    #[test]
+    fn parse_all() {
+        let server_names =
+            ServerName::parse_all(vec!["ruma.io".to_owned(), "[::1]:8448".to_owned()]).unwrap();
+        assert_eq!(server_names, ["ruma.io", "[::1]:8448"]);
+
+        assert_eq!(
+            ServerName::parse_all(vec![
+                "ruma.io".to_owned(),
+                "matrix.org".to_owned(),
+                "matrix.org:hello".to_owned(),
+                "example.com".to_owned(),
+            ]),
+            Err((2, ServerNameError::InvalidPort))
+        );
+
+        assert_eq!(ServerName::parse_all(Vec::new()), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        assert_eq!(ServerName::try_from_bytes(b"ruma.io:8080").unwrap(), "ruma.io:8080");
+        assert_eq!(ServerName::try_from_bytes(b"[::1]").unwrap(), "[::1]");
+        assert_eq!(ServerName::try_from_bytes(b"ruma\xff.io"), Err(ServerNameError::InvalidUtf8));
+        assert_eq!(
+            ServerName::try_from_bytes(b"matrix.org:hello"),
+            Err(ServerNameError::InvalidPort)
+        );
+        assert_eq!(ServerName::try_from_bytes(b""), Err(ServerNameError::Empty));
+    }
+
+    #[test]
+    fn port_server_name_resolution() {
+        // IP literals never need a lookup, with or without a port.
+        let server_name = <&ServerName>::try_from("1.2.3.4").unwrap();
+        assert!(server_name.is_ip_literal());
+        assert_eq!(server_name.port(), None);
+        let server_name = <&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap();
+        assert!(server_name.is_ip_literal());
+        assert_eq!(server_name.port(), Some(5678));
+
+        // DNS names with a port are contacted directly.
+        let server_name = <&ServerName>::try_from("example.com:8080").unwrap();
+        assert!(!server_name.is_ip_literal());
+        assert_eq!(server_name.port(), Some(8080));
+        let server_name = <&ServerName>::try_from("example.com:8448").unwrap();
+        assert_eq!(server_name.port(), Some(8448));
+
+        // DNS names without a port go through `.well-known` and SRV.
+        let server_name = <&ServerName>::try_from("example.com").unwrap();
+        assert!(!server_name.is_ip_literal());
+        assert_eq!(server_name.port(), None);
+    }
+
+    #[test]
+    fn borrow_str() {
+        let mut map = BTreeMap::new();
+        map.insert(<Box<ServerName>>::try_from("example.com:8448").unwrap(), 1);
+        map.insert(<Box<ServerName>>::try_from("[::1]").unwrap(), 2);
+
+        assert_eq!(map.get("example.com:8448"), Some(&1));
+        assert_eq!(map.get("[::1]"), Some(&2));
+        assert_eq!(map.get("example.com"), None);
+        assert_eq!(map.get("EXAMPLE.COM:8448"), None);
+
+        // Hash-based collections need the hashes to agree with those of `str`.
+        fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        for server_name in map.keys() {
+            assert_eq!(hash(server_name), hash(server_name.as_str()));
+            assert_eq!(hash(&**server_name), hash(server_name.as_str()));
+        }
+    }
+
+    #[test]
+    fn hash_same_as_str() {
+        // A small xorshift generator, so the test is reproducible without extra dependencies.
+        struct Rng(u64);
+
+        impl Rng {
+            fn next(&mut self, bound: usize) -> usize {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                (self.0 % bound as u64) as usize
+            }
+
+            fn hex(&mut self, max_digits: usize) -> String {
+                let digits = 1 + self.next(max_digits);
+                (0..digits).map(|_| char::from(b"0123456789abcdefABCDEF"[self.next(22)])).collect()
+            }
+
+            fn label(&mut self) -> String {
+                const CHARS: &[u8] =
+                    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-";
+                let len = 1 + self.next(63);
+                (0..len)
+                    .map(|i| {
+                        // Labels must not start or end with `-`.
+                        let bound =
+                            if i == 0 || i == len - 1 { CHARS.len() - 1 } else { CHARS.len() };
+                        char::from(CHARS[self.next(bound)])
+                    })
+                    .collect()
+            }
+
+            fn server_name(&mut self) -> String {
+                let host = match self.next(3) {
+                    0 => {
+                        let labels: Vec<_> = (0..1 + self.next(3)).map(|_| self.label()).collect();
+                        labels.join(".")
+                    }
+                    1 => {
+                        let octets: Vec<_> = (0..4).map(|_| self.next(256).to_string()).collect();
+                        octets.join(".")
+                    }
+                    _ => {
+                        let groups: Vec<_> = (0..8).map(|_| self.hex(4)).collect();
+                        let cut = self.next(8);
+                        let host = if self.next(2) == 0 {
+                            groups.join(":")
+                        } else {
+                            format!("{}::{}", groups[..cut].join(":"), groups[cut + 1..].join(":"))
+                        };
+                        format!("[{}]", host)
+                    }
+                };
+
+                match self.next(3) {
+                    0 => format!("{}:{}", host, self.next(65536)),
+                    _ => host,
+                }
+            }
+        }
+
+        fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..1000 {
+            let s = rng.server_name();
+            let server_name = <Box<ServerName>>::try_from(s.as_str())
+                .unwrap_or_else(|e| panic!("generated invalid server name {:?}: {}", s, e));
+
+            assert_eq!(hash(&server_name), hash(s.as_str()), "{}", s);
+            assert_eq!(hash(&*server_name), hash(s.as_str()), "{}", s);
+            assert_eq!(hash(Borrow::<str>::borrow(&*server_name)), hash(s.as_str()), "{}", s);
+        }
+    }
+
+    #[test]
+    fn delegated_server_name() {
+        let server_name = <Box<ServerName>>::try_from("matrix.example.com:8448").unwrap();
+        let delegated = DelegatedServerName::from(server_name.clone());
+
+        let deref: &ServerName = &delegated;
+        assert_eq!(deref, &*server_name);
+        assert_eq!(delegated.host(), "matrix.example.com");
+        assert_eq!(delegated.port(), Some(8448));
+        assert_eq!(delegated.as_server_name(), &*server_name);
+        assert_eq!(delegated.to_string(), "matrix.example.com:8448");
+
+        assert_eq!(Box::<ServerName>::from(delegated), server_name);
+    }
+
+    #[test]
+    fn interner() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ServerNameInterner>();
+
+        let interner = ServerNameInterner::new();
+        assert!(interner.is_empty());
+
+        let first = interner.intern(<&ServerName>::try_from("example.com:8448").unwrap());
+        let owned = <Box<ServerName>>::try_from("example.com:8448").unwrap();
+        let second = interner.intern(&owned);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(interner.len(), 1);
+
+        let other = interner.intern(<&ServerName>::try_from("example.com").unwrap());
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert_eq!(*other, *<&ServerName>::try_from("example.com").unwrap());
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn shared_from_box() {
+        // These conversions come from the blanket implementations in std, which move the value out
+        // of the box instead of going through `&ServerName`.
+        let server_name = <Box<ServerName>>::try_from("example.com:8448").unwrap();
+
+        let rc = Rc::<ServerName>::from(server_name.clone());
+        assert_eq!(*rc, *server_name);
+        assert_eq!(rc.as_str(), "example.com:8448");
+
+        let arc = Arc::<ServerName>::from(server_name.clone());
+        assert_eq!(*arc, *server_name);
+        assert_eq!(arc.port(), Some(8448));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn try_into_socket_addrs() {
+        use std::net::SocketAddr;
+
+        let server_name = <&ServerName>::try_from("1.2.3.4:5678").unwrap();
+        let addrs: Vec<_> = server_name.try_into_socket_addrs().unwrap().collect();
+        assert_eq!(addrs, vec!["1.2.3.4:5678".parse::<SocketAddr>().unwrap()]);
+
+        let server_name = <&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap();
+        let addrs: Vec<_> = server_name.try_into_socket_addrs().unwrap().collect();
+        assert_eq!(addrs, vec!["[1234:5678::abcd]:5678".parse::<SocketAddr>().unwrap()]);
+
+        let server_name = <&ServerName>::try_from("127.0.0.1").unwrap();
+        let addrs: Vec<_> = server_name.try_into_socket_addrs().unwrap().collect();
+        assert_eq!(addrs, vec!["127.0.0.1:8448".parse::<SocketAddr>().unwrap()]);
+
+        let server_name = <&ServerName>::try_from("[::1]").unwrap();
+        let addrs: Vec<_> = server_name.try_into_socket_addrs().unwrap().collect();
+        assert_eq!(addrs, vec!["[::1]:8448".parse::<SocketAddr>().unwrap()]);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn try_into_authority() {
+        use http::uri::Authority;
+
+        let server_name = <&ServerName>::try_from("[1234:5678::abcd]:8448").unwrap();
+        let authority = Authority::try_from(server_name).unwrap();
+        assert_eq!(authority.as_str(), "[1234:5678::abcd]:8448");
+        assert_eq!(authority.host(), "[1234:5678::abcd]");
+        assert_eq!(authority.port_u16(), Some(8448));
+
+        let server_name = <&ServerName>::try_from("matrix.example.com.").unwrap();
+        let authority = Authority::try_from(server_name).unwrap();
+        assert_eq!(authority.host(), "matrix.example.com.");
+        assert_eq!(authority.port_u16(), None);
+
+        let server_name = <&ServerName>::try_from("127.0.0.1:80").unwrap();
+        let authority = Authority::try_from(server_name).unwrap();
+        assert_eq!(authority.host(), "127.0.0.1");
+        assert_eq!(authority.port_u16(), Some(80));
+
+        let uri = http::Uri::builder()
+            .scheme("https")
+            .authority(Authority::try_from(server_name).unwrap())
+            .path_and_query("/_matrix/key/v2/server")
+            .build()
+            .unwrap();
+        assert_eq!(uri, "https://127.0.0.1:80/_matrix/key/v2/server");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn matches_socket_addr() {
+        use std::net::SocketAddr;
+
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        assert!(<&ServerName>::try_from("127.0.0.1:8000").unwrap().matches_socket_addr(&addr));
+        assert!(!<&ServerName>::try_from("127.0.0.1:8001").unwrap().matches_socket_addr(&addr));
+        assert!(!<&ServerName>::try_from("127.0.0.2:8000").unwrap().matches_socket_addr(&addr));
+        assert!(!<&ServerName>::try_from("127.0.0.1").unwrap().matches_socket_addr(&addr));
+
+        // Literals without a port don't match, not even the default port.
+        let addr: SocketAddr = "127.0.0.1:8448".parse().unwrap();
+        assert!(!<&ServerName>::try_from("127.0.0.1").unwrap().matches_socket_addr(&addr));
+        assert!(<&ServerName>::try_from("127.0.0.1:8448").unwrap().matches_socket_addr(&addr));
+        assert!(!<&ServerName>::try_from("[::ffff:127.0.0.1]:8448")
+            .unwrap()
+            .matches_socket_addr(&addr));
+
+        let addr: SocketAddr = "[::1]:8448".parse().unwrap();
+        assert!(!<&ServerName>::try_from("[::1]").unwrap().matches_socket_addr(&addr));
+        assert!(<&ServerName>::try_from("[::1]:8448").unwrap().matches_socket_addr(&addr));
+        assert!(<&ServerName>::try_from("[0::1]:8448").unwrap().matches_socket_addr(&addr));
+        assert!(!<&ServerName>::try_from("[::2]:8448").unwrap().matches_socket_addr(&addr));
+
+        // DNS names can't be compared without resolving them.
+        assert!(!<&ServerName>::try_from("localhost:8448").unwrap().matches_socket_addr(&addr));
+        let addr: SocketAddr = "127.0.0.1:8448".parse().unwrap();
+        assert!(!<&ServerName>::try_from("localhost").unwrap().matches_socket_addr(&addr));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn try_into_ip_addr() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        use super::NotAnIpLiteral;
+
+        let server_name = <&ServerName>::try_from("127.0.0.1").unwrap();
+        assert_eq!(IpAddr::try_from(server_name), Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+
+        let server_name = <&ServerName>::try_from("[::1]:5678").unwrap();
+        assert_eq!(IpAddr::try_from(server_name), Ok(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+
+        let server_name = <&ServerName>::try_from("example.com").unwrap();
+        assert_eq!(IpAddr::try_from(server_name), Err(NotAnIpLiteral));
+        let server_name = <&ServerName>::try_from("1.2.3.4.example.com:8448").unwrap();
+        assert_eq!(IpAddr::try_from(server_name), Err(NotAnIpLiteral));
+    }
+
+    #[test]
+    fn normalize_brackets() {
+        assert_eq!(ServerName::normalize_brackets("[127.0.0.1]").unwrap(), "127.0.0.1");
+        assert_eq!(ServerName::normalize_brackets("[127.0.0.1]:8448").unwrap(), "127.0.0.1:8448");
+        assert_eq!(ServerName::normalize_brackets("[example.com]").unwrap(), "example.com");
+        assert_eq!(
+            ServerName::normalize_brackets("[example.com]:8448").unwrap(),
+            "example.com:8448"
+        );
+
+        assert_eq!(ServerName::normalize_brackets("[::1]").unwrap(), "[::1]");
+        assert_eq!(ServerName::normalize_brackets("[::1]:8448").unwrap(), "[::1]:8448");
+        assert_eq!(ServerName::normalize_brackets("example.com").unwrap(), "example.com");
+        assert_eq!(ServerName::normalize_brackets("127.0.0.1:80").unwrap(), "127.0.0.1:80");
+
+        assert_eq!(
+            ServerName::normalize_brackets("[example.com:8448]"),
+            Err(ServerNameError::InvalidIpv6)
+        );
+        assert_eq!(
+            ServerName::normalize_brackets("[fe80::1%eth0]"),
+            Err(ServerNameError::InvalidIpv6)
+        );
+        assert_eq!(ServerName::normalize_brackets("[127.0.0.1"), Err(ServerNameError::InvalidIpv6));
+        assert_eq!(ServerName::normalize_brackets("[]"), Err(ServerNameError::Empty));
+        assert_eq!(
+            ServerName::normalize_brackets("[example.com]]"),
+            Err(ServerNameError::InvalidHost)
+        );
+        assert!(ServerName::normalize_brackets("::1").is_err());
+    }
+
+    #[test]
+    fn with_port() {
+        let server_name = <&ServerName>::try_from("example.com").unwrap();
+        assert_eq!(server_name.with_port(8448).unwrap(), "example.com:8448");
+
+        let server_name = <&ServerName>::try_from("[::1]").unwrap();
+        let with_port = server_name.with_port(8448).unwrap();
+        assert_eq!(with_port, "[::1]:8448");
+        assert_eq!(with_port.host(), "[::1]");
+        assert_eq!(with_port.port(), Some(8448));
+
+        let server_name = <&ServerName>::try_from("127.0.0.1:8000").unwrap();
+        assert_eq!(server_name.with_port(8448).unwrap(), "127.0.0.1:8448");
+        let server_name = <&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap();
+        assert_eq!(server_name.with_port(443).unwrap(), "[1234:5678::abcd]:443");
+
+        assert_eq!(server_name.with_port(0), Err(ServerNameError::InvalidPort));
+
+        let host = format!("{0}.{0}.{0}.{1}", "a".repeat(63), "a".repeat(61));
+        let server_name = <&ServerName>::try_from(host.as_str()).unwrap();
+        assert_eq!(server_name.with_port(1).unwrap().port(), Some(1));
+        assert_eq!(server_name.with_port(10), Err(ServerNameError::MaximumLengthExceeded));
+    }
+
+    #[test]
+    fn eq_with_default_port() {
+        let without_port = <&ServerName>::try_from("example.com").unwrap();
+        let with_port = <&ServerName>::try_from("example.com:8448").unwrap();
+
+        assert!(without_port.eq_with_default_port(with_port, 8448));
+        assert!(with_port.eq_with_default_port(without_port, 8448));
+        assert!(!without_port.eq_with_default_port(with_port, 443));
+        assert!(!with_port.eq_with_default_port(without_port, 443));
+
+        assert!(without_port.eq_with_default_port(without_port, 443));
+        assert!(with_port.eq_with_default_port(with_port, 443));
+
+        let other_port = <&ServerName>::try_from("example.com:8449").unwrap();
+        assert!(!with_port.eq_with_default_port(other_port, 8448));
+        let other_host = <&ServerName>::try_from("example.org:8448").unwrap();
+        assert!(!without_port.eq_with_default_port(other_host, 8448));
+
+        let ipv6 = <&ServerName>::try_from("[::1]").unwrap();
+        assert!(ipv6.eq_with_default_port(<&ServerName>::try_from("[::1]:8448").unwrap(), 8448));
+    }
+
+    #[test]
+    fn debug_truncated() {
+        let server_name = <&ServerName>::try_from("matrix.example.com:8448").unwrap();
+        assert_eq!(format!("{:?}", server_name), "matrix.example.com:8448");
+        assert_eq!(format!("{:?}", server_name.debug_truncated(23)), "matrix.example.com:8448");
+        assert_eq!(format!("{:?}", server_name.debug_truncated(100)), "matrix.example.com:8448");
+        assert_eq!(format!("{:?}", server_name.debug_truncated(22)), "matrix.example.com:844...");
+        assert_eq!(format!("{:?}", server_name.debug_truncated(6)), "matrix...");
+        assert_eq!(format!("{:?}", server_name.debug_truncated(0)), "...");
+
+        // The full server name is still available.
+        assert_eq!(server_name.to_string(), "matrix.example.com:8448");
+
+        let host = format!("{0}.{0}.{0}.{1}", "a".repeat(63), "a".repeat(61));
+        let server_name = <&ServerName>::try_from(host.as_str()).unwrap();
+        assert_eq!(format!("{:?}", server_name.debug_truncated(32)).len(), 35);
+    }
+
+    #[test]
+    fn owned_from_bytes() {
+        let server_name = <Box<ServerName>>::try_from(&b"ruma.io:8080"[..]).unwrap();
+        assert_eq!(server_name.as_str(), "ruma.io:8080");
+        let server_name = <Box<ServerName>>::try_from(b"[::1]".to_vec()).unwrap();
+        assert_eq!(server_name.as_str(), "[::1]");
+
+        assert_eq!(
+            <Box<ServerName>>::try_from(&b"ruma\xff.io"[..]),
+            Err(ServerNameError::InvalidUtf8)
+        );
+        assert_eq!(
+            <Box<ServerName>>::try_from(b"ruma\xff.io".to_vec()),
+            Err(ServerNameError::InvalidUtf8)
+        );
+
+        assert_eq!(
+            <Box<ServerName>>::try_from(&b"exa mple.com"[..]),
+            Err(ServerNameError::InvalidHost)
+        );
+        assert_eq!(
+            <Box<ServerName>>::try_from(b"matrix.org:hello".to_vec()),
+            Err(ServerNameError::InvalidPort)
+        );
+    }
+
+    #[test]
+    fn len() {
+        for &s in &[
+            "127.0.0.1",
+            "1.1.1.1:12000",
+            "[::1]",
+            "[1234:5678::abcd]:5678",
+            "example.com",
+            "ruma.io:8080",
+        ] {
+            let server_name = <&ServerName>::try_from(s).unwrap();
+            assert_eq!(server_name.len(), server_name.as_str().len());
+            assert_eq!(server_name.len(), s.len());
+            assert!(!server_name.is_empty());
+        }
+    }
+
+    #[test]
+    fn error_kinds() {
+        assert_eq!(<&ServerName>::try_from(""), Err(ServerNameError::Empty));
+        assert_eq!(<&ServerName>::try_from("[test::1]"), Err(ServerNameError::InvalidIpv6));
+        assert_eq!(<&ServerName>::try_from("[::1"), Err(ServerNameError::InvalidIpv6));
+        assert_eq!(<&ServerName>::try_from("exa mple.com"), Err(ServerNameError::InvalidHost));
+        assert_eq!(<&ServerName>::try_from("example.com/"), Err(ServerNameError::InvalidHost));
+        assert_eq!(<&ServerName>::try_from("127.0.0.1:"), Err(ServerNameError::InvalidPort));
+        assert_eq!(<&ServerName>::try_from("[fe80::1]:100000"), Err(ServerNameError::InvalidPort));
+        assert_eq!(<&ServerName>::try_from("[fe80::1]!"), Err(ServerNameError::InvalidPort));
+        assert_eq!(<&ServerName>::try_from("matrix.org:hello"), Err(ServerNameError::InvalidPort));
+        assert_eq!(
+            "matrix.org:hello".parse::<Box<ServerName>>(),
+            Err(ServerNameError::InvalidPort)
+        );
+    }
+
+    #[test]
+    fn error_into_identifier_error() {
+        assert_eq!(Error::from(ServerNameError::InvalidPort), Error::InvalidServerName);
+        assert_eq!(
+            Error::from(ServerNameError::MaximumLengthExceeded),
+            Error::MaximumLengthExceeded
+        );
+    }
+
+    #[test]
+    fn maximum_length() {
+        let label = "a".repeat(63);
+        let host = format!("{0}.{0}.{0}.{1}", label, "a".repeat(61));
+        assert_eq!(host.len(), 253);
+        assert_eq!(<&ServerName>::try_from(host.as_str()).unwrap(), host.as_str());
+
+        let server_name = format!("{}:1", host);
+        assert_eq!(server_name.len(), 255);
+        assert_eq!(<&ServerName>::try_from(server_name.as_str()).unwrap(), server_name.as_str());
+
+        let server_name = format!("{}:10", host);
+        assert_eq!(
+            <&ServerName>::try_from(server_name.as_str()),
+            Err(ServerNameError::MaximumLengthExceeded)
+        );
+    }
+
+    #[test]
+    fn dns_label_rules() {
+        let label = "a".repeat(63);
+        assert!(<&ServerName>::try_from(format!("{}.com", label).as_str()).is_ok());
+        assert_eq!(
+            <&ServerName>::try_from(format!("{}a.com", label).as_str()),
+            Err(ServerNameError::InvalidHost)
+        );
+
+        // The host must not be longer than 253 bytes, but may have a trailing dot on top of that.
+        let host = format!("{0}.{0}.{0}.{1}", label, "a".repeat(61));
+        assert!(<&ServerName>::try_from(format!("{}.", host).as_str()).is_ok());
+        assert_eq!(
+            <&ServerName>::try_from(format!("{}a", host).as_str()),
+            Err(ServerNameError::InvalidHost)
+        );
+
+        assert!(<&ServerName>::try_from("matrix-client.example.com").is_ok());
+        assert!(<&ServerName>::try_from("a.b-c.d0.1e:8448").is_ok());
+
+        for invalid in &[
+            "a..b",
+            ".example.com",
+            "example.com..",
+            ".",
+            ".:8080",
+            ":8080",
+            "-example.com",
+            "example-.com",
+            "matrix.-example.com",
+            "example.com-:8448",
+        ] {
+            assert_eq!(<&ServerName>::try_from(*invalid), Err(ServerNameError::InvalidHost));
+        }
+    }
+
+    #[test]
+    fn url_components() {
+        for invalid in
+            &["user@example.com", "example.com/path", "example.com?x", "example.com#frag", "@", "/"]
+        {
+            assert_eq!(<&ServerName>::try_from(*invalid), Err(ServerNameError::InvalidHost));
+        }
+
+        for invalid in &[
+            "example.com:8448@example.org",
+            "example.com:8448/path",
+            "example.com:8448?x",
+            "example.com:8448#frag",
+            "[::1]/path",
+            "[::1]:8448?x",
+        ] {
+            assert_eq!(<&ServerName>::try_from(*invalid), Err(ServerNameError::InvalidPort));
+        }
+
+        assert_eq!(<&ServerName>::try_from("[::1@x]"), Err(ServerNameError::InvalidIpv6));
+    }
+
+    #[test]
+    fn validate_server_name() {
+        for valid in &["ruma.io", "ruma.io:8080", "127.0.0.1", "[::1]:8448"] {
+            assert_eq!(crate::validate_server_name(valid), Ok(()));
+        }
+
+        assert_eq!(crate::validate_server_name(""), Err(ServerNameError::Empty));
+        assert_eq!(crate::validate_server_name("[test::1]"), Err(ServerNameError::InvalidIpv6));
+        assert_eq!(crate::validate_server_name("a..b"), Err(ServerNameError::InvalidHost));
+        assert_eq!(
+            crate::validate_server_name("matrix.org:hello"),
+            Err(ServerNameError::InvalidPort)
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(ServerName::parse("ruma.io:8080").unwrap(), "ruma.io:8080");
+
+        for invalid in &["", "[test::1]", "127.0.0.1:", "[fe80::1]:100000", "matrix.org:hello"] {
+            assert_eq!(ServerName::parse(invalid), <&ServerName>::try_from(*invalid));
+        }
+    }
+
+    #[test]
+    fn ip_literal() {
+        assert!(<&ServerName>::try_from("127.0.0.1").unwrap().is_ip_literal());
+        assert!(<&ServerName>::try_from("1.1.1.1:12000").unwrap().is_ip_literal());
+        assert!(<&ServerName>::try_from("[::1]").unwrap().is_ip_literal());
+        assert!(<&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap().is_ip_literal());
+        assert!(!<&ServerName>::try_from("example.com").unwrap().is_ip_literal());
+        assert!(!<&ServerName>::try_from("ruma.io:8080").unwrap().is_ip_literal());
+    }
+
+    #[test]
+    fn eq_ignore_ascii_case() {
+        let server_name = <&ServerName>::try_from("Example.com:8080").unwrap();
+        assert!(
+            server_name.eq_ignore_ascii_case(<&ServerName>::try_from("example.com:8080").unwrap())
+        );
+        assert!(
+            server_name.eq_ignore_ascii_case(<&ServerName>::try_from("EXAMPLE.COM:8080").unwrap())
+        );
+        assert!(
+            !server_name.eq_ignore_ascii_case(<&ServerName>::try_from("example.com:8448").unwrap())
+        );
+        assert!(!server_name.eq_ignore_ascii_case(<&ServerName>::try_from("example.com").unwrap()));
+
+        let server_name = <&ServerName>::try_from("[1234:5678::ABCD]").unwrap();
+        assert!(
+            server_name.eq_ignore_ascii_case(<&ServerName>::try_from("[1234:5678::abcd]").unwrap())
+        );
+        assert!(<&ServerName>::try_from("127.0.0.1")
+            .unwrap()
+            .eq_ignore_ascii_case(<&ServerName>::try_from("127.0.0.1").unwrap()));
+    }
+
+    #[test]
+    fn case_insensitive_server_name() {
+        fn hash(server_name: CaseInsensitiveServerName<'_>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            server_name.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let lower = CaseInsensitiveServerName::new(<&ServerName>::try_from("example.com").unwrap());
+        let mixed = CaseInsensitiveServerName::new(<&ServerName>::try_from("Example.com").unwrap());
+        let upper = CaseInsensitiveServerName::new(<&ServerName>::try_from("EXAMPLE.COM").unwrap());
+        let other = CaseInsensitiveServerName::new(<&ServerName>::try_from("example.org").unwrap());
+
+        assert_eq!(lower, mixed);
+        assert_eq!(hash(lower), hash(mixed));
+        assert_eq!(hash(lower), hash(upper));
+        assert_ne!(lower, other);
+        assert_eq!(mixed.as_server_name(), "Example.com");
+
+        let set: BTreeSet<_> = vec![lower, mixed, upper, other].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    #[allow(clippy::disallowed_types)]
+    fn case_insensitive_server_name_hash_set() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        for s in &["example.com", "Example.com", "EXAMPLE.COM"] {
+            set.insert(CaseInsensitiveServerName::new(<&ServerName>::try_from(*s).unwrap()));
+        }
+
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn case_insensitive_server_name_keeps_ports_distinct() {
+        let a =
+            CaseInsensitiveServerName::new(<&ServerName>::try_from("Example.com:8080").unwrap());
+        let b =
+            CaseInsensitiveServerName::new(<&ServerName>::try_from("example.com:8080").unwrap());
+        let c =
+            CaseInsensitiveServerName::new(<&ServerName>::try_from("example.com:8448").unwrap());
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn to_ascii_lowercase() {
+        assert_eq!(
+            <&ServerName>::try_from("Matrix.Example.COM:8448").unwrap().to_ascii_lowercase(),
+            "matrix.example.com:8448"
+        );
+        assert_eq!(<&ServerName>::try_from("127.0.0.1").unwrap().to_ascii_lowercase(), "127.0.0.1");
+    }
+
+    #[test]
+    fn glob() {
+        let server_name = <&ServerName>::try_from("matrix.example.com:8448").unwrap();
+        assert!(server_name.matches_glob("*.example.com"));
+        assert!(server_name.matches_glob("matrix.example.com"));
+        assert!(server_name.matches_glob("*"));
+        assert!(server_name.matches_glob("*.*.*"));
+        assert!(server_name.matches_glob("m*x.*.com"));
+        assert!(server_name.matches_glob("matrix.example.co?"));
+        assert!(!server_name.matches_glob("example.com"));
+        assert!(!server_name.matches_glob("*.example.org"));
+        assert!(!server_name.matches_glob("matrix.example.com:8448"));
+        assert!(!server_name.matches_glob("matrix.example.co"));
+
+        let server_name = <&ServerName>::try_from("matrix.org").unwrap();
+        assert!(server_name.matches_glob("matrix.?rg"));
+        assert!(!server_name.matches_glob("matrix.??rg"));
+        assert!(!server_name.matches_glob("?matrix.org"));
+        assert!(server_name.matches_glob("*matrix.org*"));
+        assert!(server_name.matches_glob("**"));
+        assert!(!server_name.matches_glob(""));
+    }
+
+    #[test]
+    fn glob_ignore_ascii_case() {
+        let server_name = <&ServerName>::try_from("Host.example.com:8448").unwrap();
+        assert!(!server_name.matches_glob("*.Example.COM"));
+        assert!(server_name.matches_glob_ignore_ascii_case("*.Example.COM"));
+        assert!(server_name.matches_glob_ignore_ascii_case("HOST.EXAMPLE.CO?"));
+        assert!(!server_name.matches_glob_ignore_ascii_case("*.example.org"));
+
+        let server_name = <&ServerName>::try_from("[::ABCD]").unwrap();
+        assert!(server_name.matches_glob_ignore_ascii_case("[::abc?]"));
+    }
+
+    #[test]
+    fn glob_multibyte_pattern() {
+        let server_name = <&ServerName>::try_from("xn--caf-dma.example").unwrap();
+        assert!(!server_name.matches_glob("café.example"));
+        assert!(server_name.matches_glob("xn--caf-dma.?xample"));
+    }
+
+    #[test]
+    fn labels() {
+        let labels = |s| <&ServerName>::try_from(s).unwrap().labels().collect::<Vec<_>>();
+
+        assert_eq!(labels("a.b.example.com"), ["a", "b", "example", "com"]);
+        assert_eq!(labels("a.b.example.com:8448"), ["a", "b", "example", "com"]);
+        assert_eq!(labels("matrix.example.com.:8448"), ["matrix", "example", "com"]);
+        assert_eq!(labels("localhost"), ["localhost"]);
+        assert_eq!(labels("1.2.3.4.5"), ["1", "2", "3", "4", "5"]);
+
+        assert!(labels("127.0.0.1").is_empty());
+        assert!(labels("127.0.0.1:8448").is_empty());
+        assert!(labels("[::1]").is_empty());
+        assert!(labels("[1234:5678::abcd]:8448").is_empty());
+    }
+
+    #[test]
+    fn host() {
+        assert_eq!(<&ServerName>::try_from("127.0.0.1").unwrap().host(), "127.0.0.1");
+        assert_eq!(<&ServerName>::try_from("1.1.1.1:12000").unwrap().host(), "1.1.1.1");
+        assert_eq!(<&ServerName>::try_from("[::1]").unwrap().host(), "[::1]");
+        assert_eq!(
+            <&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap().host(),
+            "[1234:5678::abcd]"
+        );
+        assert_eq!(<&ServerName>::try_from("example.com").unwrap().host(), "example.com");
+        assert_eq!(<&ServerName>::try_from("ruma.io:8080").unwrap().host(), "ruma.io");
+    }
+
+    #[test]
+    fn port() {
+        assert_eq!(<&ServerName>::try_from("127.0.0.1").unwrap().port(), None);
+        assert_eq!(<&ServerName>::try_from("1.1.1.1:12000").unwrap().port(), Some(12000));
+        assert_eq!(<&ServerName>::try_from("[::1]").unwrap().port(), None);
+        assert_eq!(<&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap().port(), Some(5678));
+        assert_eq!(<&ServerName>::try_from("example.com").unwrap().port(), None);
+        assert_eq!(<&ServerName>::try_from("ruma.io:8080").unwrap().port(), Some(8080));
+    }
+
+    #[test]
+    fn split_host_port() {
+        for server_name in &[
+            "127.0.0.1",
+            "1.1.1.1:12000",
+            "[::1]",
+            "[::1]:8448",
+            "[1234:5678::abcd]:5678",
+            "example.com",
+            "ruma.io:8080",
+        ] {
+            let server_name = <&ServerName>::try_from(*server_name).unwrap();
+            assert_eq!(server_name.split_host_port(), (server_name.host(), server_name.port()));
+        }
+
+        assert_eq!(
+            <&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap().split_host_port(),
+            ("[1234:5678::abcd]", Some(5678))
+        );
+        assert_eq!(<&ServerName>::try_from("[::1]").unwrap().split_host_port(), ("[::1]", None));
+    }
+
+    #[test]
+    fn semantic_server_name() {
+        let mut map = BTreeMap::new();
+        for (i, s) in [
+            "example.com:8448",
+            "example.com:100",
+            "example.com",
+            "example.com:20",
+            "example.com:443",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let server_name = <Box<ServerName>>::try_from(*s).unwrap();
+            map.insert(SemanticServerName::new(server_name), i);
+        }
+
+        let ports: Vec<_> = map.keys().map(|key| key.as_server_name().port()).collect();
+        assert_eq!(ports, [None, Some(20), Some(100), Some(443), Some(8448)]);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), [2, 3, 1, 4, 0]);
+
+        let key = SemanticServerName::from(<Box<ServerName>>::try_from("example.com:100").unwrap());
+        assert_eq!(map.get(&key), Some(&1));
+        assert_eq!(key.into_server_name(), "example.com:100");
+    }
+
+    #[test]
+    fn cmp_semantic() {
+        let port_20 = <&ServerName>::try_from("example.com:20").unwrap();
+        let port_100 = <&ServerName>::try_from("example.com:100").unwrap();
+
+        assert_eq!(port_20.cmp_semantic(port_100), Ordering::Less);
+        assert_eq!(port_20.cmp(port_100), Ordering::Greater);
+
+        let mut server_names: Vec<&ServerName> =
+            ["ruma.io", "example.com:8448", "example.com", "example.com:443", "[::1]:80"]
+                .iter()
+                .map(|s| <&ServerName>::try_from(*s).unwrap())
+                .collect();
+        server_names.sort_by(|a, b| a.cmp_semantic(b));
+
+        assert_eq!(
+            server_names,
+            ["[::1]:80", "example.com", "example.com:443", "example.com:8448", "ruma.io"]
+        );
+        assert_eq!(port_20.cmp_semantic(port_20), Ordering::Equal);
+    }
+
+    #[test]
+    fn to_uri_authority() {
+        assert_eq!(<&ServerName>::try_from("127.0.0.1").unwrap().to_uri_authority(), "127.0.0.1");
+        assert_eq!(
+            <&ServerName>::try_from("1.1.1.1:12000").unwrap().to_uri_authority(),
+            "1.1.1.1:12000"
+        );
+        assert_eq!(<&ServerName>::try_from("[::1]").unwrap().to_uri_authority(), "[::1]");
+        assert_eq!(
+            <&ServerName>::try_from("[1234:5678::abcd]:5678").unwrap().to_uri_authority(),
+            "[1234:5678::abcd]:5678"
+        );
+        assert_eq!(
+            <&ServerName>::try_from("example.com").unwrap().to_uri_authority(),
+            "example.com"
+        );
+        assert_eq!(
+            <&ServerName>::try_from("ruma.io:8080").unwrap().to_uri_authority(),
+            "ruma.io:8080"
+        );
+    }
+
+    #[test]
+    fn idn_host() {
+        let server_name = <&ServerName>::try_from("xn--nxasmq6b.example").unwrap();
+        assert_eq!(server_name.as_str(), "xn--nxasmq6b.example");
+        assert_eq!(server_name.to_unicode(), "βόλοσ.example");
+
+        assert_eq!(
+            <&ServerName>::try_from("matrix.xn--bcher-kva.example:8448").unwrap().to_unicode(),
+            "matrix.bücher.example:8448"
+        );
+        assert_eq!(<&ServerName>::try_from("XN--fiqs8s").unwrap().to_unicode(), "中国");
+    }
+
+    #[test]
+    fn idn_host_unicode_rejected() {
+        assert_eq!(<&ServerName>::try_from("βόλοσ.example"), Err(ServerNameError::InvalidHost));
+        assert_eq!(<&ServerName>::try_from("bücher.example"), Err(ServerNameError::InvalidHost));
+    }
+
+    #[test]
+    fn to_unicode_unchanged() {
+        for server_name in &["example.com", "ruma.io:8080", "127.0.0.1", "[::1]:8448"] {
+            let unicode = <&ServerName>::try_from(*server_name).unwrap().to_unicode();
+            assert!(matches!(unicode, Cow::Borrowed(_)));
+            assert_eq!(unicode, *server_name);
+        }
+
+        // Labels that aren't valid punycode are kept as-is.
+        assert_eq!(
+            <&ServerName>::try_from("xn--99999999999.xn--bcher-kva.example").unwrap().to_unicode(),
+            "xn--99999999999.bücher.example"
+        );
+    }
+
+    #[test]
+    fn decode_punycode_rfc_example() {
+        assert_eq!(
+            decode_punycode("-with-SUPER-MONKEYS-pc58ag80a8qai00g7n9n").unwrap(),
+            "安室奈美恵-with-SUPER-MONKEYS"
+        );
+        assert_eq!(decode_punycode("mnchen-3ya").unwrap(), "münchen");
+        assert_eq!(decode_punycode("hxargifdar").unwrap(), "ελληνικά");
+        assert_eq!(decode_punycode("abc!"), None);
+    }
+
+    #[test]
+    fn display_host() {
+        let server_name = <&ServerName>::try_from("example.com:8080").unwrap();
+        assert_eq!(format!("{}", server_name.display_host()), "example.com");
+        assert_eq!(format!("{}", server_name), "example.com:8080");
+
+        for (server_name, host) in &[
+            ("example.com", "example.com"),
+            ("127.0.0.1", "127.0.0.1"),
+            ("1.1.1.1:12000", "1.1.1.1"),
+            ("[::1]", "[::1]"),
+            ("[1234:5678::abcd]:5678", "[1234:5678::abcd]"),
+        ] {
+            let server_name = <&ServerName>::try_from(*server_name).unwrap();
+            assert_eq!(server_name.display_host().to_string(), *host);
+        }
+    }
+
+    #[test]
+    fn strict_port() {
+        for host in &["example.com", "127.0.0.1", "[::1]"] {
+            for port in &["0", "080", "00", "65536", "+8080", "-1", " 8080"] {
+                let server_name = format!("{}:{}", host, port);
+                assert_eq!(
+                    <&ServerName>::try_from(server_name.as_str()),
+                    Err(ServerNameError::InvalidPort),
+                    "{}",
+                    server_name
+                );
+            }
+
+            for port in &["1", "8080", "65535"] {
+                let server_name = format!("{}:{}", host, port);
+                let server_name = <&ServerName>::try_from(server_name.as_str()).unwrap();
+                assert_eq!(server_name.port(), Some(port.parse().unwrap()));
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_owned() {
+        let server_name = serde_json::from_str::<Box<ServerName>>(r#""ruma.io:8448""#).unwrap();
+        assert_eq!(server_name, "ruma.io:8448");
+
+        assert!(serde_json::from_str::<Box<ServerName>>(r#""matrix.org:hello""#).is_err());
+        assert!(serde_json::from_str::<Box<ServerName>>("null").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rc_arc_roundtrip() {
+        use std::{rc::Rc, sync::Arc};
+
+        let json = r#""ruma.io:8448""#;
+
+        let server_name = serde_json::from_str::<Rc<ServerName>>(json).unwrap();
+        assert_eq!(server_name.as_str(), "ruma.io:8448");
+        assert_eq!(serde_json::to_string(&server_name).unwrap(), json);
+
+        let server_name = serde_json::from_str::<Arc<ServerName>>(json).unwrap();
+        assert_eq!(server_name.as_str(), "ruma.io:8448");
+        assert_eq!(serde_json::to_string(&server_name).unwrap(), json);
+
+        assert!(serde_json::from_str::<Rc<ServerName>>(r#""matrix.org:hello""#).is_err());
+        assert!(serde_json::from_str::<Arc<ServerName>>(r#""matrix.org:hello""#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_borrowed() {
+        #[derive(serde::Serialize)]
+        #[serde(crate = "serde")]
+        struct Destination<'a> {
+            server_name: &'a ServerName,
+            fallback: Option<&'a ServerName>,
+        }
+
+        let server_name = <&ServerName>::try_from("ruma.io:8448").unwrap();
+        let fallback = <&ServerName>::try_from("[::1]").unwrap();
+        let destination = Destination { server_name, fallback: Some(fallback) };
+        assert_eq!(
+            serde_json::to_string(&destination).unwrap(),
+            r#"{"server_name":"ruma.io:8448","fallback":"[::1]"}"#
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("primary", server_name);
+        map.insert("secondary", fallback);
+        assert_eq!(
+            serde_json::to_string(&map).unwrap(),
+            r#"{"primary":"ruma.io:8448","secondary":"[::1]"}"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_seq() {
+        use serde::de::IntoDeserializer;
+
+        use super::deserialize_server_name_seq;
+
+        let json = r#"["matrix.org", "ruma.io:8448", "[::1]", "ruma\u002eio"]"#;
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let server_names = deserialize_server_name_seq(&mut deserializer).unwrap();
+        assert_eq!(server_names.len(), 4);
+        assert!(matches!(server_names[0], Cow::Borrowed(s) if s == "matrix.org"));
+        assert!(matches!(server_names[1], Cow::Borrowed(s) if s == "ruma.io:8448"));
+        assert!(matches!(server_names[2], Cow::Borrowed(s) if s == "[::1]"));
+        assert!(matches!(&server_names[3], Cow::Owned(s) if s.as_str() == "ruma.io"));
+
+        let json = r#"["matrix.org", "ruma.io", "example.com:hello", "[::1]"]"#;
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let err = deserialize_server_name_seq(&mut deserializer).unwrap_err();
+        assert!(err.to_string().contains("at index 2"), "{}", err);
+
+        let json = r#"["matrix.org", 42]"#;
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let err = deserialize_server_name_seq(&mut deserializer).unwrap_err();
+        assert!(err.to_string().contains("at index 1"), "{}", err);
+
+        let owned = vec!["matrix.org".to_owned()];
+        let deserializer = IntoDeserializer::<serde::de::value::Error>::into_deserializer(owned);
+        let server_names = deserialize_server_name_seq(deserializer).unwrap();
+        assert!(matches!(&server_names[0], Cow::Owned(s) if s.as_str() == "matrix.org"));
+    }
+
+    #[cfg(all(feature = "serde", feature = "compat"))]
+    #[test]
+    fn deserialize_integer_ipv4() {
+        let server_name = serde_json::from_str::<Box<ServerName>>("2130706433").unwrap();
+        assert_eq!(server_name, "127.0.0.1");
+        assert!(server_name.is_ip_literal());
+
+        assert_eq!(serde_json::from_str::<Box<ServerName>>("0").unwrap(), "0.0.0.0");
+        assert!(serde_json::from_str::<Box<ServerName>>("4294967296").is_err());
+        assert!(serde_json::from_str::<Box<ServerName>>("-1").is_err());
+    }
+
+    #[cfg(all(feature = "serde", feature = "compat"))]
+    #[test]
+    fn deserialize_map() {
+        use serde_json::{from_value as from_json_value, json};
+
+        let server_name = from_json_value::<Box<ServerName>>(json!("example.com:8448")).unwrap();
+        assert_eq!(server_name, "example.com:8448");
+
+        let server_name =
+            from_json_value::<Box<ServerName>>(json!({ "host": "example.com", "port": 8448 }))
+                .unwrap();
+        assert_eq!(server_name, "example.com:8448");
+
+        let server_name =
+            from_json_value::<Box<ServerName>>(json!({ "host": "example.com" })).unwrap();
+        assert_eq!(server_name, "example.com");
+
+        let server_name =
+            from_json_value::<Box<ServerName>>(json!({ "host": "::1", "port": 8448 })).unwrap();
+        assert_eq!(server_name, "[::1]:8448");
+        let server_name =
+            from_json_value::<Box<ServerName>>(json!({ "host": "[::1]", "port": null })).unwrap();
+        assert_eq!(server_name, "[::1]");
+
+        assert!(from_json_value::<Box<ServerName>>(json!({ "port": 8448 })).is_err());
+        assert!(from_json_value::<Box<ServerName>>(json!({ "host": "a..b" })).is_err());
+        assert!(from_json_value::<Box<ServerName>>(json!({ "host": "example.com", "port": 0 }))
+            .is_err());
+        assert!(from_json_value::<Box<ServerName>>(
+            json!({ "host": "example.com:8448", "port": 1 })
+        )
+        .is_err());
+    }
+
+    #[cfg(all(feature = "serde", not(feature = "compat")))]
+    #[test]
+    fn deserialize_integer_rejected() {
+        assert!(serde_json::from_str::<Box<ServerName>>("2130706433").is_err());
+    }
+
+    #[cfg(all(feature = "serde", not(feature = "compat")))]
+    #[test]
+    fn deserialize_map_rejected() {
+        use serde_json::{from_value as from_json_value, json};
+
+        assert!(from_json_value::<Box<ServerName>>(json!("example.com:8448")).is_ok());
+        assert!(from_json_value::<Box<ServerName>>(json!({ "host": "example.com", "port": 8448 }))
+            .is_err());
     }
 }
@@ -1,6 +1,6 @@
 //! Matrix user identifiers.
 
-use std::{convert::TryFrom, fmt, num::NonZeroU8};
+use std::{fmt, num::NonZeroU8};
 
 use crate::ServerName;
 
@@ -84,7 +84,17 @@ impl UserId {
 
     /// Returns the server name of the user ID.
     pub fn server_name(&self) -> &ServerName {
-        <&ServerName>::try_from(&self.full_id[self.colon_idx.get() as usize + 1..]).unwrap()
+        // The server name was already validated when the user ID was created.
+        ServerName::from_borrowed(&self.full_id[self.colon_idx.get() as usize + 1..])
+    }
+
+    /// Checks whether the user ID belongs to the given server.
+    ///
+    /// Server names are compared like [`ServerName::eq_ignore_ascii_case`]: the case of the host is
+    /// ignored, but the ports have to match, so `example.com` and `example.com:8448` are
+    /// different servers.
+    pub fn is_on_server(&self, server_name: &ServerName) -> bool {
+        self.server_name().eq_ignore_ascii_case(server_name)
     }
 
     /// Whether this user ID is a historical one, i.e. one that doesn't conform to the latest
@@ -238,6 +248,58 @@ mod tests {
         assert!(!user_id.is_historical());
     }
 
+    #[test]
+    fn localpart_with_special_characters() {
+        let user_id =
+            UserId::try_from("@a.b=c_d/e-f:example.com").expect("Failed to create UserId.");
+        assert_eq!(user_id.localpart(), "a.b=c_d/e-f");
+        assert!(!user_id.is_historical());
+
+        let user_id = UserId::try_from("@Carl!~'#:example.com").expect("Failed to create UserId.");
+        assert_eq!(user_id.localpart(), "Carl!~'#");
+        assert!(user_id.is_historical());
+    }
+
+    #[test]
+    fn localpart_splits_at_first_colon() {
+        let user_id = UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.");
+        assert_eq!(user_id.localpart(), "carl");
+
+        let user_id = UserId::try_from("@carl:[::1]").expect("Failed to create UserId.");
+        assert_eq!(user_id.localpart(), "carl");
+        assert_eq!(user_id.server_name(), "[::1]");
+    }
+
+    #[test]
+    fn server_name_with_port() {
+        let user_id = UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.");
+        assert_eq!(user_id.server_name(), "example.com:5000");
+        assert_eq!(user_id.server_name().host(), "example.com");
+        assert_eq!(user_id.server_name().port(), Some(5000));
+    }
+
+    #[test]
+    fn server_name_ipv6_literal() {
+        let user_id = UserId::try_from("@carl:[::1]:8448").expect("Failed to create UserId.");
+        assert_eq!(user_id.localpart(), "carl");
+        assert_eq!(user_id.server_name(), "[::1]:8448");
+        assert!(user_id.server_name().is_ip_literal());
+        assert_eq!(user_id.server_name().port(), Some(8448));
+    }
+
+    #[test]
+    fn is_on_server() {
+        let user_id = UserId::try_from("@carl:Example.com").unwrap();
+        assert!(user_id.is_on_server(<&ServerName>::try_from("example.COM").unwrap()));
+        assert!(!user_id.is_on_server(<&ServerName>::try_from("example.org").unwrap()));
+        assert!(!user_id.is_on_server(<&ServerName>::try_from("example.com:8448").unwrap()));
+
+        let user_id = UserId::try_from("@carl:example.com:8448").unwrap();
+        assert!(user_id.is_on_server(<&ServerName>::try_from("EXAMPLE.com:8448").unwrap()));
+        assert!(!user_id.is_on_server(<&ServerName>::try_from("example.com").unwrap()));
+        assert!(!user_id.is_on_server(<&ServerName>::try_from("example.com:8008").unwrap()));
+    }
+
     #[test]
     #[cfg(not(feature = "compat"))]
     fn invalid_characters_in_user_id_localpart() {
@@ -259,6 +321,34 @@ mod tests {
         assert_eq!(UserId::try_from("@carl:/").unwrap_err(), Error::InvalidServerName);
     }
 
+    #[test]
+    fn invalid_user_id_server_name() {
+        for &user_id in &[
+            "@carl:",
+            "@carl:[not::ipv6]",
+            "@carl:[::1",
+            "@carl:[::1]x",
+            "@carl:exa mple.com",
+            "@carl:example.com:0",
+        ] {
+            assert_eq!(UserId::try_from(user_id).unwrap_err(), Error::InvalidServerName);
+        }
+    }
+
+    #[test]
+    fn server_name_passes_server_name_validation() {
+        // `bad::` is a valid IPv6 address.
+        for &(user_id, server_name) in &[
+            ("@a:[bad::]", "[bad::]"),
+            ("@a:[::1]:8448", "[::1]:8448"),
+            ("@a:example.com.", "example.com."),
+        ] {
+            let user_id = UserId::try_from(user_id).unwrap();
+            assert_eq!(user_id.server_name(), server_name);
+            assert!(<&ServerName>::try_from(user_id.server_name().as_str()).is_ok());
+        }
+    }
+
     #[test]
     fn invalid_user_id_port() {
         assert_eq!(
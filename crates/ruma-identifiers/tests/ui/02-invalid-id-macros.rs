@@ -6,5 +6,6 @@ fn main() {
     let _ = ruma_identifiers::room_id!("1234567890:matrix.org");
     let _ = ruma_identifiers::room_version_id!("");
     let _ = ruma_identifiers::server_name!("");
+    let _ = ruma_identifiers::server_name!("[test::1]");
     let _ = ruma_identifiers::user_id!("user:ruma.io");
 }
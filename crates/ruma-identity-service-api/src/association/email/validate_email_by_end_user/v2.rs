@@ -26,7 +26,6 @@ ruma_api! {
         pub token: &'a str,
     }
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -36,10 +35,3 @@ impl<'a> Request<'a> {
         Self { sid, client_secret, token }
     }
 }
-
-impl Response {
-    /// Create a new empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
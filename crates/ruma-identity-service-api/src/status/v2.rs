@@ -15,7 +15,6 @@ ruma_api! {
     #[derive(Default)]
     request: {}
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -25,10 +24,3 @@ impl Request {
         Self {}
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -19,7 +19,6 @@ ruma_api! {
         pub user_accepts: Vec<String>,
     }
 
-    #[derive(Default)]
     response: {}
 }
 
@@ -29,10 +28,3 @@ impl Request {
         Self { user_accepts }
     }
 }
-
-impl Response {
-    /// Creates an empty `Response`.
-    pub fn new() -> Self {
-        Self {}
-    }
-}
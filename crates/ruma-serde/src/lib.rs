@@ -16,6 +16,7 @@ pub mod single_element_seq;
 mod strings;
 pub mod test;
 pub mod urlencoded;
+mod vec;
 
 pub use buf::{json_to_buf, slice_to_buf};
 pub use can_be_empty::{is_empty, CanBeEmpty};
@@ -31,6 +32,7 @@ pub use strings::{
     btreemap_int_or_string_to_int_values, empty_string_as_none, int_or_string_to_int,
     none_as_empty_string,
 };
+pub use vec::ignore_invalid_vec_items;
 
 /// Check whether a value is equal to its default value.
 pub fn is_default<T: Default + PartialEq>(val: &T) -> bool {
@@ -85,46 +85,48 @@ impl<T> Raw<T> {
     where
         U: Deserialize<'a>,
     {
-        struct SingleFieldVisitor<'b, T> {
-            field_name: &'b str,
-            _phantom: PhantomData<T>,
-        }
-
-        impl<'b, T> SingleFieldVisitor<'b, T> {
-            fn new(field_name: &'b str) -> Self {
-                Self { field_name, _phantom: PhantomData }
-            }
-        }
-
-        impl<'b, 'de, T> Visitor<'de> for SingleFieldVisitor<'b, T>
-        where
-            T: Deserialize<'de>,
-        {
-            type Value = Option<T>;
-
-            fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("a string")
-            }
-
-            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-            where
-                A: MapAccess<'de>,
-            {
-                let mut res = None;
-                while let Some(key) = map.next_key::<MyCowStr<'_>>()? {
-                    if key.get() == self.field_name {
-                        res = Some(map.next_value()?);
-                    } else {
-                        map.next_value::<IgnoredAny>()?;
-                    }
-                }
+        get_field(self.json(), field_name)
+    }
 
-                Ok(res)
-            }
+    /// Try to access a field nested inside of objects in this `Raw`, following the given path of
+    /// field names.
+    ///
+    /// Only the value at the end of the path is deserialized, the rest of the JSON is skipped
+    /// over. With an empty path, the whole JSON is deserialized as `U`.
+    ///
+    /// Returns `Err(_)` when the contained value or any value along the path is not an object, or
+    /// the field exists but fails to deserialize to the expected type.
+    ///
+    /// Returns `Ok(None)` when any field along the path doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn foo() -> serde_json::Result<()> {
+    /// # let raw_event: ruma_serde::Raw<()> = todo!();
+    /// let allow_ip_literals =
+    ///     raw_event.get_field_path::<bool>(&["content", "allow_ip_literals"])?.unwrap_or(true);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_field_path<'a, U>(&'a self, path: &[&str]) -> serde_json::Result<Option<U>>
+    where
+        U: Deserialize<'a>,
+    {
+        let (field_name, parents) = match path.split_last() {
+            Some(split) => split,
+            None => return self.deserialize_as().map(Some),
+        };
+
+        let mut json = self.json();
+        for parent in parents {
+            json = match get_field::<&RawValue>(json, parent)? {
+                Some(json) => json,
+                None => return Ok(None),
+            };
         }
 
-        let mut deserializer = serde_json::Deserializer::from_str(self.json().get());
-        deserializer.deserialize_map(SingleFieldVisitor::new(field_name))
+        get_field(json, field_name)
     }
 
     /// Try to deserialize the JSON as the expected type.
@@ -144,6 +146,52 @@ impl<T> Raw<T> {
     }
 }
 
+fn get_field<'a, U>(json: &'a RawValue, field_name: &str) -> serde_json::Result<Option<U>>
+where
+    U: Deserialize<'a>,
+{
+    struct SingleFieldVisitor<'b, T> {
+        field_name: &'b str,
+        _phantom: PhantomData<T>,
+    }
+
+    impl<'b, T> SingleFieldVisitor<'b, T> {
+        fn new(field_name: &'b str) -> Self {
+            Self { field_name, _phantom: PhantomData }
+        }
+    }
+
+    impl<'b, 'de, T> Visitor<'de> for SingleFieldVisitor<'b, T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = Option<T>;
+
+        fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a string")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut res = None;
+            while let Some(key) = map.next_key::<MyCowStr<'_>>()? {
+                if key.get() == self.field_name {
+                    res = Some(map.next_value()?);
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+
+            Ok(res)
+        }
+    }
+
+    let mut deserializer = serde_json::Deserializer::from_str(json.get());
+    deserializer.deserialize_map(SingleFieldVisitor::new(field_name))
+}
+
 impl<T: Serialize> From<&T> for Raw<T> {
     fn from(val: &T) -> Self {
         Self::new(serde_json::value::to_raw_value(val).unwrap())
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value as JsonValue;
+
+/// Deserialize a `Vec<T>`, silently dropping any elements that fail to deserialize as `T`.
+///
+/// To be used like this:
+/// `#[serde(deserialize_with = "ignore_invalid_vec_items")]`
+pub fn ignore_invalid_vec_items<'de, D, T>(de: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: for<'a> Deserialize<'a>,
+{
+    let values = Vec::<JsonValue>::deserialize(de)?;
+    Ok(values.into_iter().filter_map(|value| serde_json::from_value(value).ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::ignore_invalid_vec_items;
+
+    #[derive(Debug, Deserialize)]
+    struct Test {
+        #[serde(deserialize_with = "ignore_invalid_vec_items")]
+        items: Vec<String>,
+    }
+
+    #[test]
+    fn skips_invalid_items() -> serde_json::Result<()> {
+        let test: Test =
+            serde_json::from_value(json!({ "items": ["a", 1, null, "b", ["c"], { "d": "e" }] }))?;
+        assert_eq!(test.items, vec!["a".to_owned(), "b".to_owned()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn not_an_array() {
+        assert!(serde_json::from_value::<Test>(json!({ "items": "a" })).is_err());
+    }
+}
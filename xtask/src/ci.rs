@@ -64,6 +64,8 @@ impl CiTask {
         cmd!("rustup run stable cargo check --workspace --all-features").run()?;
         cmd!("rustup run stable cargo check -p ruma-client --no-default-features").run()?;
         cmd!("rustup run stable cargo check -p ruma-identifiers --no-default-features").run()?;
+        cmd!("rustup run stable cargo check -p ruma-identifiers-validation --no-default-features")
+            .run()?;
 
         // 2. Run tests
         cmd!("rustup run stable cargo test --workspace --features unstable-pre-spec").run()